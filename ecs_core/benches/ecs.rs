@@ -0,0 +1,101 @@
+use std::hint::black_box;
+
+use criterion::{BatchSize, BenchmarkId, Criterion, SamplingMode, criterion_group, criterion_main};
+use ecs_core::{Component, Entity, World};
+
+#[derive(Component, Clone, Copy)]
+struct Position {
+    x: f32,
+    y: f32,
+}
+
+#[derive(Component, Clone, Copy)]
+struct Velocity {
+    x: f32,
+    y: f32,
+}
+
+#[derive(Component, Clone, Copy)]
+#[component(storage = "SparseSet")]
+struct Marker;
+
+const SIZES: [usize; 3] = [10_000, 100_000, 1_000_000];
+
+/// Spawns `count` moving entities, every tenth of which is also marked.
+fn populate(count: usize) -> (World, Vec<Entity>) {
+    let mut world = World::new();
+    let entities = (0..count)
+        .map(|i| {
+            let entity = world.spawn((
+                Position {
+                    x: i as f32,
+                    y: 0.0,
+                },
+                Velocity { x: 1.0, y: 1.0 },
+            ));
+            if i % 10 == 0 {
+                world.add_component(&entity, Marker);
+            }
+            entity
+        })
+        .collect();
+    (world, entities)
+}
+
+fn spawn(c: &mut Criterion) {
+    let mut group = c.benchmark_group("spawn");
+    group.sampling_mode(SamplingMode::Flat).sample_size(10);
+    for count in SIZES {
+        group.bench_with_input(BenchmarkId::from_parameter(count), &count, |b, &count| {
+            b.iter(|| populate(count));
+        });
+    }
+    group.finish();
+}
+
+fn iterate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("iterate");
+    group.sampling_mode(SamplingMode::Flat).sample_size(10);
+    for count in SIZES {
+        let (mut world, _) = populate(count);
+        group.bench_with_input(BenchmarkId::new("table", count), &count, |b, _| {
+            b.iter(|| {
+                world.system::<(&mut Position, &Velocity), _>(|_, (mut pos, vel)| {
+                    pos.x += vel.x;
+                    pos.y += vel.y;
+                });
+            });
+        });
+        group.bench_with_input(BenchmarkId::new("sparse", count), &count, |b, _| {
+            b.iter(|| {
+                let mut sum = 0.0;
+                world.system::<(&Position, &Marker), _>(|_, (pos, _)| sum += pos.x);
+                black_box(sum)
+            });
+        });
+    }
+    group.finish();
+}
+
+fn despawn(c: &mut Criterion) {
+    let mut group = c.benchmark_group("despawn");
+    group.sampling_mode(SamplingMode::Flat).sample_size(10);
+    for count in SIZES {
+        group.bench_with_input(BenchmarkId::from_parameter(count), &count, |b, &count| {
+            b.iter_batched(
+                || populate(count),
+                |(mut world, entities)| {
+                    for entity in entities {
+                        world.despawn_entity(entity);
+                    }
+                    world
+                },
+                BatchSize::PerIteration,
+            );
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, spawn, iterate, despawn);
+criterion_main!(benches);
@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+
+use crate::{
+    ComponentId, EcsError, Entity, Tick,
+    ecs::{
+        ArchetypeSnapshot, ColumnSnapshot, ComponentColumn, ComponentListOps, ComponentRegistry,
+        TakenComponent,
+    },
+};
+
+pub type ArchetypeId = usize;
+
+/// Where the components of an entity are stored: its archetype, and its row in every column of
+/// that archetype.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EntityLocation {
+    pub archetype: ArchetypeId,
+    pub row: usize,
+}
+
+/// A table of every entity that has exactly the same set of components.
+///
+/// Each component type has its own column, and the components of an entity share a row across
+/// all of them, so iterating an archetype walks contiguous memory.
+pub struct Archetype {
+    id: ArchetypeId,
+    component_ids: Vec<ComponentId>,
+    columns: Vec<ComponentColumn>,
+    entities: Vec<Entity>,
+    add_edges: HashMap<ComponentId, ArchetypeId>,
+    remove_edges: HashMap<ComponentId, ArchetypeId>,
+}
+
+impl Archetype {
+    /// Creates an empty archetype. `columns` must hold one empty list per id in the sorted
+    /// `component_ids`, in the same order.
+    pub(crate) fn new(
+        id: ArchetypeId,
+        component_ids: Vec<ComponentId>,
+        columns: Vec<Box<dyn ComponentListOps>>,
+    ) -> Self {
+        Self {
+            id,
+            component_ids,
+            columns: columns.into_iter().map(ComponentColumn::new).collect(),
+            entities: vec![],
+            add_edges: HashMap::new(),
+            remove_edges: HashMap::new(),
+        }
+    }
+
+    pub fn id(&self) -> ArchetypeId {
+        self.id
+    }
+
+    /// The sorted ids of the components every entity in this archetype has.
+    pub fn component_ids(&self) -> &[ComponentId] {
+        &self.component_ids
+    }
+
+    /// The entities in this archetype, indexed by row.
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    pub fn contains(&self, id: ComponentId) -> bool {
+        self.component_ids.binary_search(&id).is_ok()
+    }
+
+    pub(crate) fn column(&self, id: ComponentId) -> Option<&ComponentColumn> {
+        let index = self.component_ids.binary_search(&id).ok()?;
+        self.columns.get(index)
+    }
+
+    pub(crate) fn column_mut(&mut self, id: ComponentId) -> Option<&mut ComponentColumn> {
+        let index = self.component_ids.binary_search(&id).ok()?;
+        self.columns.get_mut(index)
+    }
+
+    /// The archetype reached by adding the component `id`, if it has been looked up before.
+    pub(crate) fn add_edge(&self, id: ComponentId) -> Option<ArchetypeId> {
+        self.add_edges.get(&id).copied()
+    }
+
+    /// The archetype reached by removing the component `id`, if it has been looked up before.
+    pub(crate) fn remove_edge(&self, id: ComponentId) -> Option<ArchetypeId> {
+        self.remove_edges.get(&id).copied()
+    }
+
+    pub(crate) fn set_add_edge(&mut self, id: ComponentId, archetype: ArchetypeId) {
+        self.add_edges.insert(id, archetype);
+    }
+
+    pub(crate) fn set_remove_edge(&mut self, id: ComponentId, archetype: ArchetypeId) {
+        self.remove_edges.insert(id, archetype);
+    }
+
+    /// Adds `entity` to the table. Its components must be pushed onto every column.
+    ///
+    /// # Returns
+    /// `usize` - The row of the entity
+    pub(crate) fn push_entity(&mut self, entity: Entity) -> usize {
+        self.entities.push(entity);
+        self.entities.len() - 1
+    }
+
+    /// Moves the components at `row` to the end of the matching columns of `to`, dropping the
+    /// components that `to` has no column for, and removes the entity from this table.
+    ///
+    /// Columns in `skip` are left alone, for when the caller has already taken that component out.
+    ///
+    /// # Returns
+    /// `Option<Entity>` - The entity that was swapped into `row`, if any
+    pub(crate) fn move_row(
+        &mut self,
+        row: usize,
+        to: &mut Archetype,
+        skip: Option<ComponentId>,
+    ) -> Option<Entity> {
+        for (id, column) in self.component_ids.iter().zip(&mut self.columns) {
+            if Some(*id) == skip {
+                continue;
+            }
+            match to.column_mut(*id) {
+                Some(target) => column.get_mut().move_row(row, target.get_mut()),
+                None => column.get_mut().swap_remove(row),
+            }
+        }
+        self.remove_entity(row)
+    }
+
+    /// Moves each component at `row` into a list of its own, and removes the entity from this
+    /// table.
+    ///
+    /// # Returns
+    /// `(Vec<TakenComponent>, Option<Entity>)` - The components, and the entity that was swapped
+    /// into `row`, if any
+    pub(crate) fn take_row(&mut self, row: usize) -> (Vec<TakenComponent>, Option<Entity>) {
+        let taken = self
+            .component_ids
+            .iter()
+            .zip(&mut self.columns)
+            .map(|(id, column)| {
+                let mut list = column.get().empty();
+                column.get_mut().move_row(row, &mut *list);
+                TakenComponent { id: *id, list }
+            })
+            .collect();
+        (taken, self.remove_entity(row))
+    }
+
+    /// Drops the components at `row` and removes the entity from this table.
+    ///
+    /// # Returns
+    /// `Option<Entity>` - The entity that was swapped into `row`, if any
+    pub(crate) fn remove_row(&mut self, row: usize) -> Option<Entity> {
+        for column in &mut self.columns {
+            column.get_mut().swap_remove(row);
+        }
+        self.remove_entity(row)
+    }
+
+    pub(crate) fn snapshot(
+        &self,
+        registry: &ComponentRegistry,
+    ) -> Result<ArchetypeSnapshot, EcsError> {
+        let columns = self
+            .component_ids
+            .iter()
+            .zip(&self.columns)
+            .map(|(id, column)| ColumnSnapshot::capture(column.get(), *id, registry))
+            .collect::<Result<_, _>>()?;
+        Ok(ArchetypeSnapshot {
+            component_ids: self.component_ids.clone(),
+            entities: self.entities.clone(),
+            columns,
+        })
+    }
+
+    /// Replaces every row with the rows of `snapshot`, stamping them with `tick`.
+    ///
+    /// # Panics
+    /// If the snapshot was taken of an archetype with different components
+    pub(crate) fn restore(&mut self, snapshot: &ArchetypeSnapshot, tick: Tick) {
+        assert_eq!(
+            self.component_ids, snapshot.component_ids,
+            "Snapshot was taken from a different World"
+        );
+        for (column, captured) in self.columns.iter_mut().zip(&snapshot.columns) {
+            *column = ComponentColumn::new(captured.restore(tick));
+        }
+        self.entities = snapshot.entities.clone();
+    }
+
+    /// Drops every row.
+    pub(crate) fn clear(&mut self) {
+        for column in &mut self.columns {
+            *column = ComponentColumn::new(column.get().empty());
+        }
+        self.entities.clear();
+    }
+
+    fn remove_entity(&mut self, row: usize) -> Option<Entity> {
+        self.entities.swap_remove(row);
+        self.entities.get(row).cloned()
+    }
+}
@@ -0,0 +1,60 @@
+use crate::{Component, ComponentId, Entity, World};
+
+/// A group of components that is added to an entity at once, such as a tuple of components.
+///
+/// Used by `World::spawn` to find the final archetype of an entity up front, instead of moving
+/// it through an archetype per component.
+pub trait Bundle: 'static + Send + Sync {
+    /// Registers every component of the bundle, pushing their ids onto `ids`.
+    fn component_ids(world: &mut World, ids: &mut Vec<ComponentId>);
+
+    /// Pushes every component onto the storage of `entity`, which must already be in the
+    /// archetype returned for `component_ids`.
+    fn push_components(self, world: &mut World, entity: &Entity);
+}
+
+impl<T: Component> Bundle for T {
+    fn component_ids(world: &mut World, ids: &mut Vec<ComponentId>) {
+        ids.push(world.component_id::<T>());
+    }
+
+    fn push_components(self, world: &mut World, entity: &Entity) {
+        let id = world.component_id::<T>();
+        world.push_component(entity, id, self);
+    }
+}
+
+macro_rules! impl_bundle_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: Bundle),+> Bundle for ($($name,)+) {
+            fn component_ids(world: &mut World, ids: &mut Vec<ComponentId>) {
+                $($name::component_ids(world, ids);)+
+            }
+
+            #[allow(non_snake_case)]
+            fn push_components(self, world: &mut World, entity: &Entity) {
+                let ($($name,)+) = self;
+                $($name.push_components(world, entity);)+
+            }
+        }
+    };
+}
+
+macro_rules! impl_bundles {
+    (($($t:tt)+)) => {
+        impl_bundles!(@acc (), $($t)+);
+    };
+
+    (@acc ($($acc:tt)*), $t:ident, $($rest:tt)+) => {
+        impl_bundle_tuple!($($acc)* $t);
+        impl_bundles!(@acc ($($acc)* $t,), $($rest)+);
+    };
+
+    (@acc ($($acc:tt)*), $t:ident) => {
+        impl_bundle_tuple!($($acc)* $t);
+    };
+}
+
+impl_bundles!((
+    A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z
+));
@@ -0,0 +1,60 @@
+use std::ops::{Deref, DerefMut};
+
+use crate::{ComponentTicks, Tick};
+
+/// A mutable borrow of a component that only marks it as changed when it is written to.
+///
+/// Yielded by `&mut T` system parameters, so `Changed<T>` only matches components that were
+/// actually modified rather than every component a system could have modified.
+pub struct Mut<'w, T> {
+    value: &'w mut T,
+    ticks: &'w mut ComponentTicks,
+    last_run: Tick,
+    this_run: Tick,
+}
+
+impl<'w, T> Mut<'w, T> {
+    pub(crate) fn new(
+        value: &'w mut T,
+        ticks: &'w mut ComponentTicks,
+        last_run: Tick,
+        this_run: Tick,
+    ) -> Self {
+        Self {
+            value,
+            ticks,
+            last_run,
+            this_run,
+        }
+    }
+
+    /// Whether the component was added since the system last ran.
+    pub fn is_added(&self) -> bool {
+        self.ticks.is_added(self.last_run)
+    }
+
+    /// Whether the component was added or changed since the system last ran.
+    pub fn is_changed(&self) -> bool {
+        self.ticks.is_changed(self.last_run)
+    }
+
+    /// Mutably borrows the component without marking it as changed.
+    pub fn bypass_change_detection(&mut self) -> &mut T {
+        self.value
+    }
+}
+
+impl<T> Deref for Mut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.value
+    }
+}
+
+impl<T> DerefMut for Mut<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.ticks.changed = self.this_run;
+        self.value
+    }
+}
@@ -0,0 +1,179 @@
+use crate::{Access, Component, Entity, Resource, SystemParam, UnsafeWorldCell, World};
+
+type Command = Box<dyn FnOnce(&mut World, &mut Vec<Entity>) + Send + Sync>;
+
+/// Operations recorded by `Commands`, applied to the `World` in order at the next sync point.
+///
+/// Every `World` starts with one as a resource. `World::system` applies it once the system has
+/// finished, and a `Schedule` applies it after each system that uses `Commands`.
+#[derive(Default)]
+pub struct CommandQueue {
+    commands: Vec<Command>,
+    spawned: usize,
+}
+impl Resource for CommandQueue {}
+
+impl CommandQueue {
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Applies every recorded operation to `world`, emptying the queue.
+    pub fn apply(&mut self, world: &mut World) {
+        let mut spawned = Vec::with_capacity(self.spawned);
+        for command in self.commands.drain(..) {
+            command(world, &mut spawned);
+        }
+        self.spawned = 0;
+    }
+
+    fn push(&mut self, command: impl FnOnce(&mut World, &mut Vec<Entity>) + Send + Sync + 'static) {
+        self.commands.push(Box::new(command));
+    }
+}
+
+/// The entity an `EntityCommands` operates on. Entities spawned through `Commands` only get an
+/// `Entity` once the queue is applied, so they are referred to by the order they were spawned in.
+#[derive(Clone)]
+enum Target {
+    Existing(Entity),
+    Spawned(usize),
+}
+
+impl Target {
+    fn resolve(&self, spawned: &[Entity]) -> Entity {
+        match self {
+            Target::Existing(entity) => entity.clone(),
+            Target::Spawned(index) => spawned[*index].clone(),
+        }
+    }
+}
+
+/// Records structural changes to the `World` while it is borrowed by a system.
+///
+/// Nothing happens until the `CommandQueue` is applied, so the running system never sees its own
+/// changes. Operations on entities that have been despawned by then are skipped.
+///
+/// Like any parameter, `Commands` in a `World::system` is fetched once per matching entity.
+/// Systems that only record commands, without a component, should be run with
+/// `World::resource_system` or a `ResourceSystem` so they record them once per run.
+pub struct Commands<'w> {
+    queue: &'w mut CommandQueue,
+}
+
+impl<'w> Commands<'w> {
+    pub fn new(queue: &'w mut CommandQueue) -> Self {
+        Self { queue }
+    }
+
+    /// Spawns a new entity, which components can then be inserted on.
+    pub fn spawn(&mut self) -> EntityCommands<'_> {
+        let index = self.queue.spawned;
+        self.queue.spawned += 1;
+        self.queue
+            .push(|world, spawned| spawned.push(world.spawn_entity()));
+
+        EntityCommands {
+            queue: self.queue,
+            target: Target::Spawned(index),
+        }
+    }
+
+    /// Records operations on an existing `entity`.
+    pub fn entity(&mut self, entity: &Entity) -> EntityCommands<'_> {
+        EntityCommands {
+            queue: self.queue,
+            target: Target::Existing(entity.clone()),
+        }
+    }
+
+    pub fn despawn(&mut self, entity: &Entity) {
+        self.entity(entity).despawn();
+    }
+
+    /// Records an arbitrary operation on the `World`.
+    pub fn add(&mut self, command: impl FnOnce(&mut World) + Send + Sync + 'static) {
+        self.queue.push(|world, _| command(world));
+    }
+}
+
+/// Records operations on a single entity, see `Commands`.
+pub struct EntityCommands<'a> {
+    queue: &'a mut CommandQueue,
+    target: Target,
+}
+
+impl EntityCommands<'_> {
+    /// Adds `component` to the entity, replacing the existing one if it already has a `T`.
+    pub fn insert<T: Component>(&mut self, component: T) -> &mut Self {
+        let target = self.target.clone();
+        self.queue.push(move |world, spawned| {
+            let entity = target.resolve(spawned);
+            if world.is_alive(&entity) {
+                world.insert_component(&entity, component);
+            }
+        });
+        self
+    }
+
+    /// Removes the `T` component from the entity, if it has one.
+    pub fn remove<T: Component>(&mut self) -> &mut Self {
+        let target = self.target.clone();
+        self.queue.push(move |world, spawned| {
+            world.remove_component::<T>(&target.resolve(spawned));
+        });
+        self
+    }
+
+    pub fn despawn(&mut self) {
+        let target = self.target.clone();
+        self.queue.push(move |world, spawned| {
+            // The entity may already have been despawned by an earlier command.
+            let _ = world.try_despawn(target.resolve(spawned));
+        });
+    }
+
+    /// Despawns the entity along with all of its descendants.
+    pub fn despawn_recursive(&mut self) {
+        let target = self.target.clone();
+        self.queue.push(move |world, spawned| {
+            let _ = world.try_despawn_recursive(target.resolve(spawned));
+        });
+    }
+
+    /// Makes the entity a child of `parent`, see `World::set_parent`.
+    pub fn set_parent(&mut self, parent: &Entity) -> &mut Self {
+        let target = self.target.clone();
+        let parent = parent.clone();
+        self.queue.push(move |world, spawned| {
+            let entity = target.resolve(spawned);
+            if world.is_alive(&entity) && world.is_alive(&parent) {
+                world.set_parent(&entity, &parent);
+            }
+        });
+        self
+    }
+}
+
+// SAFETY: `fetch` only borrows the `CommandQueue` resource, which `access` registers as a resource
+// write.
+unsafe impl<'w> SystemParam<'w> for Commands<'_> {
+    type Item = Commands<'w>;
+
+    fn access(world: &mut World, access: &mut Access) {
+        access.add_resource_write(world.resource_id::<CommandQueue>());
+        access.add_deferred();
+    }
+
+    unsafe fn fetch(world: UnsafeWorldCell<'w>, _entity: &Entity) -> Option<Self::Item> {
+        unsafe { Self::fetch_once(world) }
+    }
+
+    unsafe fn fetch_once(world: UnsafeWorldCell<'w>) -> Option<Self::Item> {
+        unsafe { world.get_resource_mut::<CommandQueue>() }.map(Commands::new)
+    }
+}
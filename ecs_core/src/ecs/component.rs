@@ -1,15 +1,75 @@
 use std::{
-    any::{Any, TypeId},
+    any::{Any, TypeId, type_name},
+    cell::UnsafeCell,
     collections::HashMap,
 };
 
+use crate::{
+    DynamicLayout, EcsError,
+    ecs::{DynamicComponent, SnapshotFns},
+};
+
 pub type ComponentId = u32;
 
-pub trait Component: 'static + Send + Sync {}
+/// A point in time of a `World`, advanced every time a system runs.
+pub type Tick = u64;
+
+/// When a component was added to its entity and when it was last changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComponentTicks {
+    pub added: Tick,
+    pub changed: Tick,
+}
+
+impl ComponentTicks {
+    pub fn new(tick: Tick) -> Self {
+        Self {
+            added: tick,
+            changed: tick,
+        }
+    }
+
+    /// Whether the component was added after `last_run`.
+    pub fn is_added(&self, last_run: Tick) -> bool {
+        self.added > last_run
+    }
+
+    /// Whether the component was added or changed after `last_run`.
+    pub fn is_changed(&self, last_run: Tick) -> bool {
+        self.changed > last_run
+    }
+}
+
+/// How the components of a type are stored in a `World`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum StorageType {
+    /// In the columns of the entity's archetype. Fastest to iterate, but adding or removing the
+    /// component moves the entity to another archetype.
+    #[default]
+    Table,
+    /// In a separate set keyed by entity. Cheap to add and remove, for components such as markers
+    /// that are toggled often.
+    SparseSet,
+}
+
+pub trait Component: 'static + Send + Sync {
+    const STORAGE_TYPE: StorageType = StorageType::Table;
+}
 
 pub struct ComponentRegistry {
     next: ComponentId,
     lookup_map: HashMap<TypeId, ComponentId>,
+    /// Components registered at runtime, keyed by name since they have no Rust type.
+    dynamic_map: HashMap<&'static str, ComponentId>,
+    /// `None` for components registered at runtime.
+    type_ids: Vec<Option<TypeId>>,
+    /// Only set for components registered at runtime.
+    layouts: Vec<Option<DynamicLayout>>,
+    names: Vec<&'static str>,
+    storage_types: Vec<StorageType>,
+    constructors: Vec<fn() -> Box<dyn ComponentListOps>>,
+    /// Only set for types registered with `World::register_snapshot`.
+    snapshot_fns: Vec<Option<SnapshotFns>>,
 }
 
 impl ComponentRegistry {
@@ -17,6 +77,13 @@ impl ComponentRegistry {
         Self {
             next: 0,
             lookup_map: HashMap::new(),
+            dynamic_map: HashMap::new(),
+            type_ids: Vec::new(),
+            layouts: Vec::new(),
+            names: Vec::new(),
+            storage_types: Vec::new(),
+            constructors: Vec::new(),
+            snapshot_fns: Vec::new(),
         }
     }
 
@@ -29,38 +96,230 @@ impl ComponentRegistry {
     /// # Panics
     /// If the total number of registered components exceeds the maximum number that can exist
     pub fn id<T: Component>(&mut self) -> ComponentId {
+        match self.get::<T>() {
+            Some(id) => id,
+            None => {
+                let id = self.add(
+                    type_name::<T>(),
+                    T::STORAGE_TYPE,
+                    || Box::new(ComponentList::<T>::new()),
+                    None,
+                );
+                self.lookup_map.insert(TypeId::of::<T>(), id);
+                self.type_ids[id as usize] = Some(TypeId::of::<T>());
+                id
+            }
+        }
+    }
+
+    /// Registers a component without a Rust type, stored as `DynamicValue`s with `layout`.
+    /// Registering the same name with the same layout and storage again returns the existing id.
+    ///
+    /// # Returns
+    /// `Option<ComponentId>` - The id of the component, or `None` if `name` is already registered
+    /// with a different layout or storage
+    ///
+    /// # Panics
+    /// If the total number of registered components exceeds the maximum number that can exist
+    pub(crate) fn register_dynamic(
+        &mut self,
+        name: &str,
+        layout: DynamicLayout,
+        storage_type: StorageType,
+    ) -> Option<ComponentId> {
+        if let Some(&id) = self.dynamic_map.get(name) {
+            let index = id as usize;
+            return (self.layouts[index].as_ref() == Some(&layout)
+                && self.storage_types[index] == storage_type)
+                .then_some(id);
+        }
+
+        // Names are kept for the lifetime of the program like those of Rust types, and dynamic
+        // components are only registered once per name.
+        let name: &'static str = Box::leak(name.into());
+        let id = self.add(
+            name,
+            storage_type,
+            || Box::new(ComponentList::<DynamicComponent>::new()),
+            Some(SnapshotFns::of::<DynamicComponent>()),
+        );
+        self.dynamic_map.insert(name, id);
+        self.layouts[id as usize] = Some(layout);
+        Some(id)
+    }
+
+    /// Checks that the component registered in `from` as `id` can be imported, i.e. that it is
+    /// not a dynamic component whose name is registered here with a different layout or storage.
+    ///
+    /// # Returns
+    /// `Result<(), EcsError>` - An error if the component can't be imported
+    ///
+    /// # Panics
+    /// If `id` is not registered in `from`
+    pub(crate) fn check_import(
+        &self,
+        from: &ComponentRegistry,
+        id: ComponentId,
+    ) -> Result<(), EcsError> {
+        let index = id as usize;
+        if from.type_ids[index].is_some() {
+            return Ok(());
+        }
+        match self.dynamic_map.get(from.names[index]) {
+            Some(&existing)
+                if self.layouts[existing as usize] != from.layouts[index]
+                    || self.storage_types[existing as usize] != from.storage_types[index] =>
+            {
+                Err(EcsError::DynamicComponentConflict(from.names[index]))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Retrieves the id of the type registered in `from` as `id`, registering it with the same
+    /// name, storage and constructors if needed. Used to move components between `World`s,
+    /// where the same type usually has a different id.
+    ///
+    /// # Panics
+    /// If `id` is not registered in `from`, it can't be imported, see
+    /// `ComponentRegistry::check_import`, or the total number of registered components exceeds
+    /// the maximum number that can exist
+    pub(crate) fn import(&mut self, from: &ComponentRegistry, id: ComponentId) -> ComponentId {
+        if let Err(e) = self.check_import(from, id) {
+            panic!("{e}");
+        }
+
+        let index = id as usize;
+        let existing = match from.type_ids[index] {
+            Some(type_id) => self.lookup_map.get(&type_id),
+            None => self.dynamic_map.get(from.names[index]),
+        };
+        if let Some(id) = existing {
+            return *id;
+        }
+
+        let id = self.add(
+            from.names[index],
+            from.storage_types[index],
+            from.constructors[index],
+            from.snapshot_fns[index],
+        );
+        match from.type_ids[index] {
+            Some(type_id) => {
+                self.lookup_map.insert(type_id, id);
+            }
+            None => {
+                self.dynamic_map.insert(from.names[index], id);
+            }
+        }
+        self.type_ids[id as usize] = from.type_ids[index];
+        self.layouts[id as usize] = from.layouts[index].clone();
+        id
+    }
+
+    fn add(
+        &mut self,
+        name: &'static str,
+        storage_type: StorageType,
+        constructor: fn() -> Box<dyn ComponentListOps>,
+        snapshot_fns: Option<SnapshotFns>,
+    ) -> ComponentId {
         if self.next == ComponentId::MAX {
             panic!("Exceeding maximum component types in ComponentRegistry");
         }
 
-        let type_id = TypeId::of::<T>();
+        let id = self.next;
+        self.next += 1;
+        self.type_ids.push(None);
+        self.layouts.push(None);
+        self.names.push(name);
+        self.storage_types.push(storage_type);
+        self.constructors.push(constructor);
+        self.snapshot_fns.push(snapshot_fns);
+        id
+    }
 
-        self.lookup_map
-            .entry(type_id)
-            .or_insert({
-                let id = self.next;
-                self.next += 1;
-                id
-            })
-            .clone()
+    /// Retrieves the id for the type that implements `Component` without registering it.
+    ///
+    /// # Returns
+    /// `Option<ComponentId>` - The id of the Component item, or `None` if it was never registered
+    pub fn get<T: Component>(&self) -> Option<ComponentId> {
+        self.lookup_map.get(&TypeId::of::<T>()).copied()
+    }
+
+    /// The number of registered components. Ids are handed out in order, starting at 0.
+    pub(crate) fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Retrieves the id of a component registered at runtime with
+    /// `World::register_dynamic_component`.
+    pub fn dynamic_id(&self, name: &str) -> Option<ComponentId> {
+        self.dynamic_map.get(name).copied()
+    }
+
+    /// Retrieves the layout of a component registered at runtime, or `None` for Rust types.
+    pub fn layout(&self, id: ComponentId) -> Option<&DynamicLayout> {
+        self.layouts.get(id as usize)?.as_ref()
+    }
+
+    /// Retrieves the type name a `ComponentId` was registered with.
+    pub fn name(&self, id: ComponentId) -> Option<&'static str> {
+        self.names.get(id as usize).copied()
+    }
+
+    /// Retrieves how the components registered with a `ComponentId` are stored.
+    pub fn storage_type(&self, id: ComponentId) -> Option<StorageType> {
+        self.storage_types.get(id as usize).copied()
+    }
+
+    /// Lets the components of `T` be cloned into and compared in a `WorldSnapshot`.
+    pub(crate) fn register_snapshot<T: Component + Clone + PartialEq>(&mut self) {
+        let id = self.id::<T>();
+        self.snapshot_fns[id as usize] = Some(SnapshotFns::of::<T>());
+    }
+
+    pub(crate) fn snapshot_fns(&self, id: ComponentId) -> Option<SnapshotFns> {
+        self.snapshot_fns.get(id as usize).copied().flatten()
+    }
+
+    /// Creates an empty list for the components registered with a `ComponentId`.
+    pub(crate) fn new_list(&self, id: ComponentId) -> Option<Box<dyn ComponentListOps>> {
+        self.constructors
+            .get(id as usize)
+            .map(|constructor| constructor())
     }
 }
 
 pub struct ComponentList<T: Component> {
     pub components: Vec<T>,
+    pub ticks: Vec<ComponentTicks>,
 }
 
 impl<T: Component> ComponentList<T> {
     pub fn new() -> Self {
-        Self { components: vec![] }
+        Self {
+            components: vec![],
+            ticks: vec![],
+        }
     }
 }
 
 pub trait ComponentListOps: 'static + Send + Sync {
     fn len(&self) -> usize;
-    fn push_boxed(&mut self, item: Box<dyn Any>);
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
     fn swap_remove(&mut self, index: usize);
-    fn at<'a>(&'a mut self, index: usize) -> &'a mut dyn Any;
+    /// Swap removes the component at `index` and pushes it, with its ticks, onto `to`.
+    ///
+    /// # Panics
+    /// If `to` does not hold the same component type
+    fn move_row(&mut self, index: usize, to: &mut dyn ComponentListOps);
+    /// A new, empty list of the same component type.
+    fn empty(&self) -> Box<dyn ComponentListOps>;
+    fn ticks(&self) -> &[ComponentTicks];
+    fn ticks_mut(&mut self) -> &mut [ComponentTicks];
     fn as_any(&self) -> &dyn Any;
     fn as_any_mut(&mut self) -> &mut dyn Any;
 }
@@ -69,15 +328,26 @@ impl<T: Component> ComponentListOps for ComponentList<T> {
     fn len(&self) -> usize {
         self.components.len()
     }
-    fn at<'a>(&'a mut self, index: usize) -> &'a mut dyn Any {
-        &mut self.components[index]
-    }
-    fn push_boxed(&mut self, item: Box<dyn Any>) {
-        let item = *item.downcast::<T>().expect("Component type mismatch");
-        self.components.push(item);
-    }
     fn swap_remove(&mut self, index: usize) {
         self.components.swap_remove(index);
+        self.ticks.swap_remove(index);
+    }
+    fn move_row(&mut self, index: usize, to: &mut dyn ComponentListOps) {
+        let to = to
+            .as_any_mut()
+            .downcast_mut::<ComponentList<T>>()
+            .expect("Component type mismatch");
+        to.components.push(self.components.swap_remove(index));
+        to.ticks.push(self.ticks.swap_remove(index));
+    }
+    fn empty(&self) -> Box<dyn ComponentListOps> {
+        Box::new(ComponentList::<T>::new())
+    }
+    fn ticks(&self) -> &[ComponentTicks] {
+        &self.ticks
+    }
+    fn ticks_mut(&mut self) -> &mut [ComponentTicks] {
+        &mut self.ticks
     }
     fn as_any(&self) -> &dyn Any {
         self
@@ -86,3 +356,36 @@ impl<T: Component> ComponentListOps for ComponentList<T> {
         self
     }
 }
+
+/// A type-erased `ComponentList` that can be mutably borrowed through a shared reference.
+///
+/// Used as the columns of an `Archetype`, so that `UnsafeWorldCell` can let several system
+/// parameters borrow different lists without first borrowing the whole `World` mutably.
+pub(crate) struct ComponentColumn(UnsafeCell<Box<dyn ComponentListOps>>);
+
+// SAFETY: `ComponentListOps` is `Send + Sync`. Unsynchronised mutable access only happens through
+// `get_unchecked_mut`, whose callers must guarantee the list is not borrowed elsewhere.
+unsafe impl Sync for ComponentColumn {}
+
+impl ComponentColumn {
+    pub(crate) fn new(list: Box<dyn ComponentListOps>) -> Self {
+        Self(UnsafeCell::new(list))
+    }
+
+    pub(crate) fn get(&self) -> &dyn ComponentListOps {
+        // SAFETY: Mutable borrows only exist through `get_mut`, which needs `&mut self`, or
+        // `get_unchecked_mut`, whose callers guarantee there are no other borrows.
+        unsafe { &**self.0.get() }
+    }
+
+    pub(crate) fn get_mut(&mut self) -> &mut dyn ComponentListOps {
+        &mut **self.0.get_mut()
+    }
+
+    /// # Safety
+    /// No other reference to this list may be live for the lifetime of the returned borrow.
+    #[allow(clippy::mut_from_ref)]
+    pub(crate) unsafe fn get_unchecked_mut(&self) -> &mut dyn ComponentListOps {
+        unsafe { &mut **self.0.get() }
+    }
+}
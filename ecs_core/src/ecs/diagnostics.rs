@@ -0,0 +1,211 @@
+use std::{
+    fmt::{self, Debug, Display},
+    time::Duration,
+};
+
+use crate::{
+    ArchetypeId, ComponentId, ComponentList, EcsError, Entity, Stage, StorageType, World,
+    ecs::DynamicComponent,
+};
+
+/// What a `World` holds and how long its systems took, for logging or an in-game overlay.
+///
+/// `World::diagnostics` fills in everything but `systems`, which come from the `Schedule` that
+/// runs them, see `Schedule::diagnostics`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Diagnostics {
+    pub entity_count: usize,
+    /// Every archetype, including empty ones.
+    pub archetypes: Vec<ArchetypeDiagnostics>,
+    /// Every registered component, in registration order.
+    pub components: Vec<ComponentDiagnostics>,
+    pub systems: Vec<SystemDiagnostics>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchetypeDiagnostics {
+    pub id: ArchetypeId,
+    /// The names of its table components.
+    pub components: Vec<&'static str>,
+    pub entity_count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentDiagnostics {
+    pub id: ComponentId,
+    pub name: &'static str,
+    pub storage_type: StorageType,
+    /// The number of components stored, across every archetype for table components.
+    pub count: usize,
+}
+
+/// The last run of a system in a `Schedule`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SystemDiagnostics {
+    pub name: &'static str,
+    pub stage: Stage,
+    /// Wall time, including applying the `Commands` of single-threaded runs.
+    pub duration: Duration,
+    /// The number of entities the system ran for, or `None` for systems that do not run per
+    /// entity.
+    pub matched_entities: Option<usize>,
+}
+
+impl Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} entities in {} archetypes",
+            self.entity_count,
+            self.archetypes.len()
+        )?;
+        for archetype in self.archetypes.iter().filter(|a| a.entity_count > 0) {
+            writeln!(
+                f,
+                "  archetype {} [{}]: {}",
+                archetype.id,
+                archetype.components.join(", "),
+                archetype.entity_count
+            )?;
+        }
+        for component in &self.components {
+            writeln!(
+                f,
+                "  {} ({:?}): {}",
+                component.name, component.storage_type, component.count
+            )?;
+        }
+        for system in &self.systems {
+            write!(
+                f,
+                "  {} ({:?}): {:.3} ms",
+                system.name,
+                system.stage,
+                system.duration.as_secs_f64() * 1000.0
+            )?;
+            match system.matched_entities {
+                Some(matched) => writeln!(f, ", {matched} entities")?,
+                None => writeln!(f)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Formats an entity with all of its components, see `World::debug_entity`.
+pub struct EntityDebug<'w> {
+    world: &'w World,
+    entity: Entity,
+}
+
+impl Debug for EntityDebug<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Entity")
+            .field("id", &self.entity.id())
+            .field("generation", &self.entity.generation())
+            .field("components", &Components(self))
+            .finish()
+    }
+}
+
+struct Components<'a, 'w>(&'a EntityDebug<'w>);
+
+impl Debug for Components<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let EntityDebug { world, entity } = self.0;
+        let registry = world.component_registry();
+
+        let mut map = f.debug_map();
+        for id in world.component_ids_of(entity) {
+            let name = registry.name(id).unwrap_or("unknown");
+            let value = match world.type_registry().get_by_id(id) {
+                Some(registration) => registration.reflect(world, entity).map(|value| {
+                    value
+                        .and_then(|value| {
+                            ron::to_string(&value).map_err(|e| EcsError::Reflect(e.to_string()))
+                        })
+                        .unwrap_or_else(|e| e.to_string())
+                }),
+                None => dynamic_value(world, entity, id),
+            };
+            map.entry(&name, &format_args!("{}", value.as_deref().unwrap_or("..")));
+        }
+        map.finish()
+    }
+}
+
+fn dynamic_value(world: &World, entity: &Entity, id: ComponentId) -> Option<String> {
+    let (column, row) = world.component_column(entity, id)?;
+    let component = column
+        .get()
+        .as_any()
+        .downcast_ref::<ComponentList<DynamicComponent>>()?
+        .components
+        .get(row)?;
+    Some(match component.0.as_value() {
+        Some(value) => ron::to_string(value).unwrap_or_else(|e| e.to_string()),
+        None => format!("{:?}", component.0.as_bytes().unwrap_or_default()),
+    })
+}
+
+impl World {
+    /// Counts the entities, archetypes and components of the `World`.
+    pub fn diagnostics(&self) -> Diagnostics {
+        let registry = self.component_registry();
+        let archetypes: Vec<ArchetypeDiagnostics> = self
+            .archetypes()
+            .iter()
+            .map(|archetype| ArchetypeDiagnostics {
+                id: archetype.id(),
+                components: archetype
+                    .component_ids()
+                    .iter()
+                    .map(|id| registry.name(*id).unwrap_or("unknown"))
+                    .collect(),
+                entity_count: archetype.len(),
+            })
+            .collect();
+
+        let components = (0..registry.len() as ComponentId)
+            .filter_map(|id| {
+                let storage_type = registry.storage_type(id)?;
+                let count = match storage_type {
+                    StorageType::Table => self
+                        .archetypes()
+                        .iter()
+                        .filter(|archetype| archetype.contains(id))
+                        .map(|archetype| archetype.len())
+                        .sum(),
+                    StorageType::SparseSet => {
+                        self.sparse_set(id).map_or(0, |set| set.entities().len())
+                    }
+                };
+                Some(ComponentDiagnostics {
+                    id,
+                    name: registry.name(id)?,
+                    storage_type,
+                    count,
+                })
+            })
+            .collect();
+
+        Diagnostics {
+            entity_count: archetypes.iter().map(|a| a.entity_count).sum(),
+            archetypes,
+            components,
+            systems: vec![],
+        }
+    }
+
+    /// Formats `entity` with all of its components. Components registered in the
+    /// `TypeRegistry` and dynamic components are shown with their value, others as `..`.
+    ///
+    /// # Returns
+    /// `Option<EntityDebug>` - Or `None` if the entity is not alive
+    pub fn debug_entity(&self, entity: &Entity) -> Option<EntityDebug<'_>> {
+        self.is_alive(entity).then(|| EntityDebug {
+            world: self,
+            entity: entity.clone(),
+        })
+    }
+}
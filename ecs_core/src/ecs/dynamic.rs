@@ -0,0 +1,390 @@
+use crate::{
+    Access, Component, ComponentId, ComponentList, EcsError, Entity, HookKind, QueryState,
+    ReflectValue, StorageType, Tick, World, ecs::ComponentColumn,
+};
+
+/// The layout of a component registered at runtime with `World::register_dynamic_component`,
+/// e.g. by a mod or a script.
+///
+/// A layout only describes which `DynamicValue`s are accepted. Dynamic components are stored
+/// like any other component, as one `DynamicValue` per entity, so each value owns its own heap
+/// allocation rather than being packed into a column of raw bytes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DynamicLayout {
+    /// A `DynamicValue::Bytes` of exactly this many bytes. No alignment is guaranteed.
+    Bytes(usize),
+    /// A serde value with the same shape as the schema: maps have the same keys, sequences hold
+    /// elements shaped like the first element of the schema's, and every other value has the
+    /// same kind. `None` matches any option.
+    Schema(ReflectValue),
+}
+
+impl DynamicLayout {
+    /// Whether `value` can be stored in a component with this layout.
+    pub fn matches(&self, value: &DynamicValue) -> bool {
+        match (self, value) {
+            (DynamicLayout::Bytes(size), DynamicValue::Bytes(bytes)) => bytes.len() == *size,
+            (DynamicLayout::Schema(schema), DynamicValue::Value(value)) => {
+                matches_schema(schema, value)
+            }
+            _ => false,
+        }
+    }
+}
+
+fn matches_schema(schema: &ReflectValue, value: &ReflectValue) -> bool {
+    match (schema, value) {
+        (ReflectValue::Map(schema), ReflectValue::Map(value)) => {
+            schema.len() == value.len()
+                && schema.iter().all(|(key, schema)| {
+                    value
+                        .get(key)
+                        .is_some_and(|value| matches_schema(schema, value))
+                })
+        }
+        (ReflectValue::Seq(schema), ReflectValue::Seq(value)) => match schema.first() {
+            Some(schema) => value.iter().all(|value| matches_schema(schema, value)),
+            None => true,
+        },
+        (ReflectValue::Option(Some(schema)), ReflectValue::Option(Some(value))) => {
+            matches_schema(schema, value)
+        }
+        (ReflectValue::Option(_), ReflectValue::Option(_)) => true,
+        _ => std::mem::discriminant(schema) == std::mem::discriminant(value),
+    }
+}
+
+/// The value of a component registered at runtime.
+///
+/// Its layout is checked when it is inserted, see `World::try_insert_dynamic`. Changes made
+/// through a mutable borrow are not checked.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DynamicValue {
+    Bytes(Vec<u8>),
+    Value(ReflectValue),
+}
+
+impl DynamicValue {
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            DynamicValue::Bytes(bytes) => Some(bytes),
+            DynamicValue::Value(_) => None,
+        }
+    }
+
+    /// The bytes of the value. Returned as a slice so the size can't change.
+    pub fn as_bytes_mut(&mut self) -> Option<&mut [u8]> {
+        match self {
+            DynamicValue::Bytes(bytes) => Some(bytes),
+            DynamicValue::Value(_) => None,
+        }
+    }
+
+    pub fn as_value(&self) -> Option<&ReflectValue> {
+        match self {
+            DynamicValue::Value(value) => Some(value),
+            DynamicValue::Bytes(_) => None,
+        }
+    }
+
+    pub fn as_value_mut(&mut self) -> Option<&mut ReflectValue> {
+        match self {
+            DynamicValue::Value(value) => Some(value),
+            DynamicValue::Bytes(_) => None,
+        }
+    }
+}
+
+/// What the columns of every dynamic component hold. Not public, so it can never be registered
+/// as a Rust component of its own.
+#[derive(Clone, PartialEq)]
+pub(crate) struct DynamicComponent(pub(crate) DynamicValue);
+impl Component for DynamicComponent {}
+
+/// A query over component ids rather than Rust types, run with `World::dynamic_system`.
+///
+/// Fetched components are numbered in the order they were added with `DynamicQuery::read` and
+/// `DynamicQuery::write`.
+#[derive(Default)]
+pub struct DynamicQuery {
+    fetch: Vec<(ComponentId, bool)>,
+    with: Vec<ComponentId>,
+    without: Vec<ComponentId>,
+    state: QueryState,
+}
+
+impl DynamicQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requires the component and borrows it immutably.
+    pub fn read(mut self, id: ComponentId) -> Self {
+        self.fetch.push((id, false));
+        self
+    }
+
+    /// Requires the component and borrows it mutably.
+    pub fn write(mut self, id: ComponentId) -> Self {
+        self.fetch.push((id, true));
+        self
+    }
+
+    /// Requires the component without fetching it.
+    pub fn with(mut self, id: ComponentId) -> Self {
+        self.with.push(id);
+        self
+    }
+
+    /// Only matches entities that do not have the component.
+    pub fn without(mut self, id: ComponentId) -> Self {
+        self.without.push(id);
+        self
+    }
+}
+
+/// The components fetched by a `DynamicQuery` for one entity.
+pub struct DynamicItem<'w> {
+    columns: Vec<(&'w ComponentColumn, usize, bool)>,
+    this_run: Tick,
+}
+
+impl DynamicItem<'_> {
+    /// The number of fetched components.
+    pub fn len(&self) -> usize {
+        self.columns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty()
+    }
+
+    /// Borrows the `index`th fetched component, if it is a `T`.
+    pub fn get<T: Component>(&self, index: usize) -> Option<&T> {
+        let (column, row, _) = self.columns.get(index)?;
+        column
+            .get()
+            .as_any()
+            .downcast_ref::<ComponentList<T>>()?
+            .components
+            .get(*row)
+    }
+
+    /// Mutably borrows the `index`th fetched component, if it is a `T` fetched with
+    /// `DynamicQuery::write`, marking it as changed.
+    pub fn get_mut<T: Component>(&mut self, index: usize) -> Option<&mut T> {
+        let (column, row, write) = self.columns.get(index)?;
+        if !write {
+            return None;
+        }
+        // SAFETY: `World::dynamic_system` rejected queries that borrow a component twice, and
+        // `&mut self` keeps any other borrow of this item from being live.
+        let list = unsafe { column.get_unchecked_mut() }
+            .as_any_mut()
+            .downcast_mut::<ComponentList<T>>()?;
+        list.ticks.get_mut(*row)?.changed = self.this_run;
+        list.components.get_mut(*row)
+    }
+
+    /// Borrows the `index`th fetched component, if it was registered at runtime.
+    pub fn dynamic(&self, index: usize) -> Option<&DynamicValue> {
+        self.get::<DynamicComponent>(index)
+            .map(|component| &component.0)
+    }
+
+    /// Mutably borrows the `index`th fetched component, if it was registered at runtime and
+    /// fetched with `DynamicQuery::write`, marking it as changed.
+    pub fn dynamic_mut(&mut self, index: usize) -> Option<&mut DynamicValue> {
+        self.get_mut::<DynamicComponent>(index)
+            .map(|component| &mut component.0)
+    }
+}
+
+impl World {
+    /// Registers a component that has no Rust type, stored as `DynamicValue`s with `layout`.
+    /// Registering the same name again with the same layout and storage returns the same id.
+    ///
+    /// # Returns
+    /// `ComponentId` - The id to insert and query the component with
+    ///
+    /// # Panics
+    /// If `name` is already registered with a different layout or storage
+    pub fn register_dynamic_component(
+        &mut self,
+        name: &str,
+        layout: DynamicLayout,
+        storage_type: StorageType,
+    ) -> ComponentId {
+        match self
+            .component_registry_mut()
+            .register_dynamic(name, layout, storage_type)
+        {
+            Some(id) => id,
+            None => panic!("Dynamic component `{name}` is already registered with another layout"),
+        }
+    }
+
+    /// The id of the component registered at runtime under `name`.
+    pub fn dynamic_component_id(&self, name: &str) -> Option<ComponentId> {
+        self.component_registry().dynamic_id(name)
+    }
+
+    /// Adds the dynamic component `id` to `entity`, replacing the existing one if the entity
+    /// already has it.
+    ///
+    /// # Panics
+    /// If the entity is not alive, `id` is not a dynamic component, or `value` does not match its
+    /// layout. See `World::try_insert_dynamic` for a non-panicking version.
+    pub fn insert_dynamic(
+        &mut self,
+        entity: &Entity,
+        id: ComponentId,
+        value: DynamicValue,
+    ) -> Option<DynamicValue> {
+        match self.try_insert_dynamic(entity, id, value) {
+            Ok(replaced) => replaced,
+            Err(e) => panic!("{e}"),
+        }
+    }
+
+    /// Adds the dynamic component `id` to `entity`, replacing the existing one if the entity
+    /// already has it.
+    ///
+    /// # Returns
+    /// `Result<Option<DynamicValue>, EcsError>` - The value that was replaced, if any, or an
+    /// error if the entity is not alive, `id` is not a dynamic component, or `value` does not
+    /// match its layout
+    pub fn try_insert_dynamic(
+        &mut self,
+        entity: &Entity,
+        id: ComponentId,
+        value: DynamicValue,
+    ) -> Result<Option<DynamicValue>, EcsError> {
+        let registry = self.component_registry();
+        let layout = registry.layout(id).ok_or(EcsError::NotDynamic(id))?;
+        if !layout.matches(&value) {
+            return Err(EcsError::DynamicLayoutMismatch(
+                registry.name(id).unwrap_or("unknown"),
+            ));
+        }
+
+        match self.get_dynamic_mut(entity, id) {
+            Some(existing) => {
+                let replaced = std::mem::replace(existing, value);
+                self.trigger_hooks(HookKind::Insert, id, entity);
+                Ok(Some(replaced))
+            }
+            None => {
+                self.try_add_component_with_id(entity, id, DynamicComponent(value))?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Borrows the dynamic component `id` of `entity`.
+    pub fn get_dynamic(&self, entity: &Entity, id: ComponentId) -> Option<&DynamicValue> {
+        let (column, row) = self.component_column(entity, id)?;
+        column
+            .get()
+            .as_any()
+            .downcast_ref::<ComponentList<DynamicComponent>>()?
+            .components
+            .get(row)
+            .map(|component| &component.0)
+    }
+
+    /// Mutably borrows the dynamic component `id` of `entity`, marking it as changed at the
+    /// current tick.
+    pub fn get_dynamic_mut(
+        &mut self,
+        entity: &Entity,
+        id: ComponentId,
+    ) -> Option<&mut DynamicValue> {
+        let change_tick = self.change_tick();
+        let (column, row) = self.component_column_mut(entity, id)?;
+        let list = column
+            .get_mut()
+            .as_any_mut()
+            .downcast_mut::<ComponentList<DynamicComponent>>()?;
+        list.ticks.get_mut(row)?.changed = change_tick;
+        list.components
+            .get_mut(row)
+            .map(|component| &mut component.0)
+    }
+
+    /// Removes the dynamic component `id` from `entity`.
+    ///
+    /// # Returns
+    /// `Option<DynamicValue>` - The removed value, or `None` if the entity did not have one
+    pub fn remove_dynamic(&mut self, entity: &Entity, id: ComponentId) -> Option<DynamicValue> {
+        self.component_registry().layout(id)?;
+        self.remove_component_with_id::<DynamicComponent>(entity, id)
+            .map(|component| component.0)
+    }
+
+    /// Runs `f` for every entity that matches `query`. Works with components registered at
+    /// runtime as well as Rust components, as long as their id is known.
+    ///
+    /// # Panics
+    /// If the query uses an id that is not registered, or borrows the same component more than
+    /// once.
+    pub fn dynamic_system<F>(&mut self, query: &mut DynamicQuery, mut f: F)
+    where
+        F: FnMut(Entity, DynamicItem<'_>),
+    {
+        let access = self.dynamic_access(query);
+        let this_run = self.reserve_ticks(1);
+        let entities = self.query_entities(&mut query.state, &access);
+
+        for entity in &entities {
+            if !self.matches_sparse(&access, entity) {
+                continue;
+            }
+            let columns = query
+                .fetch
+                .iter()
+                .map(|(id, write)| {
+                    self.component_column(entity, *id)
+                        .map(|(column, row)| (column, row, *write))
+                })
+                .collect::<Option<Vec<_>>>();
+            if let Some(columns) = columns {
+                f(entity.clone(), DynamicItem { columns, this_run });
+            }
+        }
+
+        query.state.reuse_entities(entities);
+    }
+
+    fn dynamic_access(&self, query: &DynamicQuery) -> Access {
+        let registry = self.component_registry();
+        let storage_type = |id: ComponentId| match registry.storage_type(id) {
+            Some(storage_type) => storage_type,
+            None => panic!("Query uses component {id}, which is not registered"),
+        };
+
+        let mut access = Access::default();
+        for (id, write) in &query.fetch {
+            access.require_stored(*id, storage_type(*id));
+            if *write {
+                access.add_write(*id);
+            } else {
+                access.add_read(*id);
+            }
+        }
+        for id in &query.with {
+            access.require_stored(*id, storage_type(*id));
+        }
+        for id in &query.without {
+            access.exclude_stored(*id, storage_type(*id));
+        }
+
+        if let Some(id) = access.conflicts().first() {
+            panic!(
+                "Query requests conflicting access to component `{}`",
+                registry.name(*id).unwrap_or("unknown")
+            );
+        }
+        access
+    }
+}
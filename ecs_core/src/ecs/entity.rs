@@ -1,42 +1,108 @@
+use serde::{Deserialize, Serialize};
+
 type EntityId = u32;
 
-#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Entity {
     id: EntityId,
     generation: EntityId,
 }
 
+impl Entity {
+    pub fn id(&self) -> EntityId {
+        self.id
+    }
+
+    pub fn generation(&self) -> EntityId {
+        self.generation
+    }
+}
+
+/// The current generation of an id, and whether an `Entity` with that generation is alive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct EntitySlot {
+    generation: EntityId,
+    alive: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct EntityRegistry {
-    free: Vec<Entity>,
-    next: EntityId,
+    slots: Vec<EntitySlot>,
+    free: Vec<EntityId>,
+    retired: usize,
 }
 
 impl EntityRegistry {
     pub fn new() -> Self {
         Self {
+            slots: vec![],
             free: vec![],
-            next: 0,
+            retired: 0,
         }
     }
 
+    /// Creates a new `Entity`, recycling the id of a removed one when possible.
+    ///
+    /// # Panics
+    /// If every `EntityId` is either alive or retired
     pub fn new_entity(&mut self) -> Entity {
-        if let Some(mut free) = self.free.pop() {
-            if free.generation != EntityId::MAX {
-                free.generation += 1;
-                return free;
-            }
+        if let Some(id) = self.free.pop() {
+            let slot = &mut self.slots[id as usize];
+            slot.alive = true;
+            return Entity {
+                id,
+                generation: slot.generation,
+            };
         }
 
-        if self.next == EntityId::MAX {
+        if self.slots.len() >= EntityId::MAX as usize {
             panic!("Maximum number of entities exceeded");
         }
 
-        let id = self.next;
-        self.next += 1;
+        let id = self.slots.len() as EntityId;
+        self.slots.push(EntitySlot {
+            generation: 0,
+            alive: true,
+        });
         Entity { id, generation: 0 }
     }
 
-    pub fn remove_entity(&mut self, entity: Entity) {
-        self.free.push(entity);
+    /// Whether `entity` refers to a live entity, rather than a removed one whose id may since
+    /// have been recycled.
+    pub fn is_alive(&self, entity: &Entity) -> bool {
+        self.slots
+            .get(entity.id as usize)
+            .is_some_and(|slot| slot.alive && slot.generation == entity.generation)
+    }
+
+    /// Removes `entity`, freeing its id for reuse with the next generation.
+    ///
+    /// Once an id has been used by every generation it is retired instead, so a stale handle
+    /// can never match a newer entity. The free list therefore never holds more than one entry
+    /// per id.
+    ///
+    /// # Returns
+    /// `bool` - Whether the entity was alive
+    pub fn remove_entity(&mut self, entity: &Entity) -> bool {
+        if !self.is_alive(entity) {
+            return false;
+        }
+
+        let slot = &mut self.slots[entity.id as usize];
+        slot.alive = false;
+
+        if slot.generation == EntityId::MAX {
+            self.retired += 1;
+        } else {
+            slot.generation += 1;
+            self.free.push(entity.id);
+        }
+
+        true
+    }
+
+    /// The number of ids that exhausted every generation and will never be handed out again.
+    pub fn retired(&self) -> usize {
+        self.retired
     }
 }
@@ -0,0 +1,143 @@
+use std::fmt::{self, Display};
+
+use crate::{ComponentId, Entity};
+
+/// Errors returned by the fallible `World` operations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EcsError {
+    /// The entity was despawned, or its id has been recycled by a newer generation.
+    EntityNotFound(Entity),
+    /// The entity already has a component of this type.
+    ComponentAlreadyExists {
+        entity: Entity,
+        component: &'static str,
+    },
+    /// Two systems in a `Schedule` share a name.
+    DuplicateSystem(&'static str),
+    /// A system is ordered against a system that is not in the `Schedule`.
+    UnknownSystem {
+        system: &'static str,
+        dependency: &'static str,
+    },
+    /// The ordering constraints of these systems form a cycle.
+    SystemCycle(Vec<&'static str>),
+    /// A system must run before another one that is in an earlier stage.
+    StageOrderConflict {
+        before: &'static str,
+        after: &'static str,
+    },
+    /// The reflected component has no field with this name.
+    UnknownField { component: String, field: String },
+    /// A value could not be converted to or from a component.
+    Reflect(String),
+    /// The parent is the child itself, or one of its descendants.
+    HierarchyCycle { child: Entity, parent: Entity },
+    /// No type is registered under this name in the `TypeRegistry`.
+    UnknownTypeName(String),
+    /// The entity does not have the registered component.
+    MissingComponent {
+        entity: Entity,
+        component: &'static str,
+    },
+    /// Another type is already registered under this name in the `TypeRegistry`.
+    TypeNameTaken {
+        name: &'static str,
+        type_name: &'static str,
+    },
+    /// The type is already registered in the `TypeRegistry` under another name.
+    TypeAlreadyRegistered {
+        type_name: &'static str,
+        name: &'static str,
+    },
+    /// A scene could not be read from or written to RON.
+    SceneFormat(String),
+    /// The same entity appears more than once in a scene.
+    DuplicateSceneEntity(Entity),
+    /// A scene or transfer refers to an entity it does not contain, see `EntityMap`.
+    UnknownSceneEntity(Entity),
+    /// The `World` holds components of a type that was not registered with
+    /// `World::register_snapshot`.
+    NotSnapshottable(&'static str),
+    /// The component id was not registered with `World::register_dynamic_component`.
+    NotDynamic(ComponentId),
+    /// A value does not have the layout its dynamic component was registered with.
+    DynamicLayoutMismatch(&'static str),
+    /// A dynamic component is registered in two `World`s under the same name, but with a
+    /// different layout or storage.
+    DynamicComponentConflict(&'static str),
+}
+
+impl Display for EcsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EcsError::EntityNotFound(entity) => write!(f, "Entity not found in World: {entity:?}"),
+            EcsError::ComponentAlreadyExists { entity, component } => write!(
+                f,
+                "Entity already contains specified component: {entity:?} already has `{component}`"
+            ),
+            EcsError::DuplicateSystem(name) => {
+                write!(f, "Schedule already contains a system named `{name}`")
+            }
+            EcsError::UnknownSystem { system, dependency } => write!(
+                f,
+                "System `{system}` is ordered against `{dependency}`, which is not in the schedule"
+            ),
+            EcsError::SystemCycle(systems) => write!(
+                f,
+                "Systems have cyclic ordering constraints: {}",
+                systems.join(", ")
+            ),
+            EcsError::StageOrderConflict { before, after } => write!(
+                f,
+                "System `{before}` must run before `{after}`, but is in a later stage"
+            ),
+            EcsError::UnknownField { component, field } => {
+                write!(f, "Component `{component}` has no field `{field}`")
+            }
+            EcsError::Reflect(message) => write!(f, "Reflection failed: {message}"),
+            EcsError::HierarchyCycle { child, parent } => write!(
+                f,
+                "Cannot parent {child:?} to {parent:?}, which is the entity itself or one of its descendants"
+            ),
+            EcsError::UnknownTypeName(name) => {
+                write!(f, "No type is registered under the name `{name}`")
+            }
+            EcsError::MissingComponent { entity, component } => {
+                write!(f, "Entity {entity:?} does not have component `{component}`")
+            }
+            EcsError::TypeNameTaken { name, type_name } => {
+                write!(
+                    f,
+                    "Type name `{name}` is already registered for `{type_name}`"
+                )
+            }
+            EcsError::TypeAlreadyRegistered { type_name, name } => {
+                write!(f, "Type `{type_name}` is already registered as `{name}`")
+            }
+            EcsError::SceneFormat(message) => write!(f, "Invalid scene: {message}"),
+            EcsError::DuplicateSceneEntity(entity) => {
+                write!(f, "Scene contains entity {entity:?} more than once")
+            }
+            EcsError::UnknownSceneEntity(entity) => {
+                write!(f, "Entity {entity:?} is not part of the scene or transfer")
+            }
+            EcsError::NotSnapshottable(component) => write!(
+                f,
+                "Component `{component}` must be registered with `World::register_snapshot`"
+            ),
+            EcsError::NotDynamic(id) => {
+                write!(f, "Component {id} is not a dynamic component")
+            }
+            EcsError::DynamicLayoutMismatch(component) => write!(
+                f,
+                "Value does not match the layout of dynamic component `{component}`"
+            ),
+            EcsError::DynamicComponentConflict(component) => write!(
+                f,
+                "Dynamic component `{component}` is registered with a different layout or storage"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for EcsError {}
@@ -0,0 +1,197 @@
+use std::{any::TypeId, collections::HashMap, sync::Mutex};
+
+use crate::{Access, Entity, Resource, SystemParam, Tick, UnsafeWorldCell, World};
+
+/// A message sent from one system to others, such as a collision or an input.
+pub trait Event: 'static + Send + Sync {}
+
+/// An event and the tick of the system run that sent it.
+struct EventInstance<E> {
+    tick: Tick,
+    event: E,
+}
+
+/// Double-buffered storage for every `E` event sent in the current and previous update.
+///
+/// `Events::update` drops the older buffer, so an event can be read until the end of the update
+/// after the one it was sent in. `World::update_events` updates every event type added with
+/// `World::add_event`.
+pub struct Events<E: Event> {
+    previous: Vec<EventInstance<E>>,
+    current: Vec<EventInstance<E>>,
+}
+impl<E: Event> Resource for Events<E> {}
+
+impl<E: Event> Default for Events<E> {
+    fn default() -> Self {
+        Self {
+            previous: vec![],
+            current: vec![],
+        }
+    }
+}
+
+impl<E: Event> Events<E> {
+    /// Sends `event` as if it was sent by the system run at `tick`.
+    pub fn send(&mut self, event: E, tick: Tick) {
+        self.current.push(EventInstance { tick, event });
+    }
+
+    /// Drops the events sent before the previous update.
+    pub fn update(&mut self) {
+        std::mem::swap(&mut self.previous, &mut self.current);
+        self.current.clear();
+    }
+
+    /// Drops every stored event.
+    pub fn clear(&mut self) {
+        self.previous.clear();
+        self.current.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.previous.len() + self.current.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterates over the events sent from `since` up to, but not including, `until`, oldest first.
+    pub fn iter_between(&self, since: Tick, until: Tick) -> impl Iterator<Item = &E> {
+        self.previous
+            .iter()
+            .chain(self.current.iter())
+            .filter(move |instance| instance.tick >= since && instance.tick < until)
+            .map(|instance| &instance.event)
+    }
+}
+
+/// Sends `E` events. Sent events are stamped with the tick of the running system.
+///
+/// Entities only match while the `Events<E>` resource exists.
+pub struct EventWriter<'w, E: Event> {
+    events: &'w mut Events<E>,
+    tick: Tick,
+}
+
+impl<E: Event> EventWriter<'_, E> {
+    pub fn send(&mut self, event: E) {
+        self.events.send(event, self.tick);
+    }
+
+    pub fn send_batch(&mut self, events: impl IntoIterator<Item = E>) {
+        for event in events {
+            self.send(event);
+        }
+    }
+}
+
+/// Reads the `E` events sent since the running system last read them.
+///
+/// Each system keeps its own cursor, which moves past the events it reads the first time the
+/// reader is fetched in a run, so a system that never fetches it does not miss any event. Every
+/// entity of a single run sees the same events, so systems that only read events should be run
+/// with `World::resource_system` or a `ResourceSystem` to see each event once. Events sent by
+/// the system itself are read in its next run.
+///
+/// Entities only match while the `Events<E>` resource exists.
+pub struct EventReader<'w, E: Event> {
+    events: &'w Events<E>,
+    since: Tick,
+    until: Tick,
+}
+
+impl<E: Event> EventReader<'_, E> {
+    pub fn iter(&self) -> impl Iterator<Item = &E> {
+        self.events.iter_between(self.since, self.until)
+    }
+
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+}
+
+// SAFETY: `fetch` only borrows the `Events<E>` resource, which `access` registers as a resource
+// write.
+unsafe impl<'w, E: Event> SystemParam<'w> for EventWriter<'_, E> {
+    type Item = EventWriter<'w, E>;
+
+    fn access(world: &mut World, access: &mut Access) {
+        access.add_resource_write(world.resource_id::<Events<E>>());
+    }
+
+    unsafe fn fetch(world: UnsafeWorldCell<'w>, _entity: &Entity) -> Option<Self::Item> {
+        unsafe { Self::fetch_once(world) }
+    }
+
+    unsafe fn fetch_once(world: UnsafeWorldCell<'w>) -> Option<Self::Item> {
+        let events = unsafe { world.get_resource_mut::<Events<E>>() }?;
+        Some(EventWriter {
+            events,
+            tick: world.this_run(),
+        })
+    }
+}
+
+// SAFETY: `fetch` only reads the `Events<E>` resource, which `access` registers as a resource read.
+unsafe impl<'w, E: Event> SystemParam<'w> for EventReader<'_, E> {
+    type Item = EventReader<'w, E>;
+
+    fn access(world: &mut World, access: &mut Access) {
+        access.add_resource_read(world.resource_id::<Events<E>>());
+    }
+
+    unsafe fn fetch(world: UnsafeWorldCell<'w>, _entity: &Entity) -> Option<Self::Item> {
+        unsafe { Self::fetch_once(world) }
+    }
+
+    unsafe fn fetch_once(world: UnsafeWorldCell<'w>) -> Option<Self::Item> {
+        let events = unsafe { world.get_resource::<Events<E>>() }?;
+        let (since, until) = match world.event_cursors() {
+            Some(cursors) => cursors.read(TypeId::of::<E>(), world.this_run()),
+            None => (world.last_run(), world.this_run()),
+        };
+        Some(EventReader {
+            events,
+            since,
+            until,
+        })
+    }
+}
+
+/// Where a system is in reading an event type, see `EventReader`.
+#[derive(Default, Clone, Copy)]
+struct EventCursor {
+    /// Every event sent before this tick has been read.
+    read_until: Tick,
+    /// The run that last moved the cursor, and where the cursor was before that run.
+    run: Tick,
+    run_since: Tick,
+}
+
+/// The `EventReader` cursors of a system, per event type. Kept in its `QueryState`.
+#[derive(Default)]
+pub(crate) struct EventCursors(Mutex<HashMap<TypeId, EventCursor>>);
+
+impl EventCursors {
+    /// Moves the cursor for the events of type `id` past every event sent before `this_run`, on
+    /// the first call for each run.
+    ///
+    /// # Returns
+    /// `(Tick, Tick)` - The ticks to read events between in the run at `this_run`
+    fn read(&self, id: TypeId, this_run: Tick) -> (Tick, Tick) {
+        let mut cursors = self.0.lock().unwrap();
+        let cursor = cursors.entry(id).or_default();
+        if cursor.run != this_run {
+            cursor.run_since = cursor.read_until;
+            cursor.read_until = this_run;
+            cursor.run = this_run;
+        }
+        (cursor.run_since, this_run)
+    }
+}
@@ -0,0 +1,266 @@
+use std::{
+    any::Any,
+    cmp::Reverse,
+    collections::BinaryHeap,
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, Condvar, Mutex},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use crate::{System, Tick, UnsafeWorldCell, World};
+
+/// How a `Schedule` runs its systems.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutorKind {
+    /// Every system runs on the calling thread, one after another.
+    #[default]
+    SingleThreaded,
+    /// Systems whose access does not conflict run at the same time on worker threads, which the
+    /// `Schedule` starts on its first run and keeps until it is dropped.
+    MultiThreaded,
+}
+
+/// Worker threads kept alive between runs, so a parallel run does not start new threads for
+/// every batch. Owned by the `Schedule` that uses it.
+pub(crate) struct ThreadPool {
+    shared: Arc<PoolShared>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+struct PoolShared {
+    state: Mutex<PoolState>,
+    /// Signalled when a job is posted or the pool shuts down.
+    work: Condvar,
+    /// Signalled when a worker finishes the current job.
+    done: Condvar,
+}
+
+struct PoolState {
+    /// The job every worker runs once. Only borrowed for the duration of `ThreadPool::broadcast`.
+    job: Option<&'static (dyn Fn() + Sync)>,
+    /// Incremented for every job, so a worker runs each one exactly once.
+    generation: u64,
+    running: usize,
+    panic: Option<Box<dyn Any + Send>>,
+    shutdown: bool,
+}
+
+impl ThreadPool {
+    /// Starts one worker per available core.
+    pub(crate) fn new() -> Self {
+        let shared = Arc::new(PoolShared {
+            state: Mutex::new(PoolState {
+                job: None,
+                generation: 0,
+                running: 0,
+                panic: None,
+                shutdown: false,
+            }),
+            work: Condvar::new(),
+            done: Condvar::new(),
+        });
+        let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+        let workers = (0..threads)
+            .map(|index| {
+                let shared = shared.clone();
+                thread::Builder::new()
+                    .name(format!("ecs-worker-{index}"))
+                    .spawn(move || shared.work())
+                    .expect("Failed to start an executor thread")
+            })
+            .collect();
+
+        Self { shared, workers }
+    }
+
+    /// Runs `job` once on every worker and waits until all of them have returned.
+    ///
+    /// # Panics
+    /// If `job` panicked on any worker, once every worker has returned
+    fn broadcast(&self, job: &(dyn Fn() + Sync)) {
+        // SAFETY: The job is only reachable through `PoolState::job`, which is cleared below
+        // after every worker has finished running it, so it never outlives the borrow.
+        let job: &'static (dyn Fn() + Sync) = unsafe { std::mem::transmute(job) };
+
+        let mut state = self.shared.state.lock().unwrap();
+        state.job = Some(job);
+        state.generation += 1;
+        state.running = self.workers.len();
+        self.shared.work.notify_all();
+        while state.running > 0 {
+            state = self.shared.done.wait(state).unwrap();
+        }
+        state.job = None;
+
+        if let Some(payload) = state.panic.take() {
+            drop(state);
+            panic::resume_unwind(payload);
+        }
+    }
+}
+
+impl PoolShared {
+    fn work(&self) {
+        let mut generation = 0;
+        loop {
+            let job = {
+                let mut state = self.state.lock().unwrap();
+                loop {
+                    if state.shutdown {
+                        return;
+                    }
+                    if state.generation != generation
+                        && let Some(job) = state.job
+                    {
+                        generation = state.generation;
+                        break job;
+                    }
+                    state = self.work.wait(state).unwrap();
+                }
+            };
+
+            let result = panic::catch_unwind(AssertUnwindSafe(job));
+
+            let mut state = self.state.lock().unwrap();
+            if let Err(payload) = result {
+                state.panic.get_or_insert(payload);
+            }
+            state.running -= 1;
+            if state.running == 0 {
+                self.done.notify_all();
+            }
+        }
+    }
+}
+
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.shared.state.lock() {
+            state.shutdown = true;
+        }
+        self.shared.work.notify_all();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// The systems of a parallel run that are ready to start, and how many dependencies the others
+/// are still waiting on.
+struct Progress {
+    ready: BinaryHeap<Reverse<usize>>,
+    remaining: Vec<usize>,
+    finished: usize,
+    panicked: bool,
+    durations: Vec<Duration>,
+}
+
+/// Wakes the other workers if the system being run panics, so they stop waiting for it.
+struct PanicGuard<'a> {
+    progress: &'a Mutex<Progress>,
+    condvar: &'a Condvar,
+}
+
+impl Drop for PanicGuard<'_> {
+    fn drop(&mut self) {
+        if thread::panicking() {
+            if let Ok(mut progress) = self.progress.lock() {
+                progress.panicked = true;
+            }
+            self.condvar.notify_all();
+        }
+    }
+}
+
+/// Runs `systems` on the workers of `pool`, starting each one once every system listed in its
+/// `dependencies` has finished.
+///
+/// Each system is stamped with the tick it would have had in a sequential run of `systems`, so
+/// change detection sees the same results either way.
+///
+/// # Returns
+/// `Vec<Duration>` - The wall time each system took to run
+///
+/// # Panics
+/// If a system panics, once the systems already running have finished
+///
+/// # Safety
+/// Every system must be initialized and non-exclusive, and any two systems whose `Access` is
+/// not compatible must be ordered by `dependencies`, which may only point to earlier systems.
+pub(crate) unsafe fn run_parallel(
+    pool: &ThreadPool,
+    world: &mut World,
+    systems: Vec<&mut dyn System>,
+    dependencies: &[Vec<usize>],
+) -> Vec<Duration> {
+    let len = systems.len();
+    let first_tick = world.reserve_ticks(len);
+
+    let mut dependents = vec![vec![]; len];
+    for (index, dependencies) in dependencies.iter().enumerate() {
+        for &dependency in dependencies {
+            dependents[dependency].push(index);
+        }
+    }
+
+    let progress = Mutex::new(Progress {
+        ready: (0..len)
+            .filter(|&index| dependencies[index].is_empty())
+            .map(Reverse)
+            .collect(),
+        remaining: dependencies.iter().map(Vec::len).collect(),
+        finished: 0,
+        panicked: false,
+        durations: vec![Duration::ZERO; len],
+    });
+    let condvar = Condvar::new();
+    let tasks: Vec<Mutex<Option<&mut dyn System>>> = systems
+        .into_iter()
+        .map(|system| Mutex::new(Some(system)))
+        .collect();
+    let world = UnsafeWorldCell::new(world, first_tick, first_tick);
+
+    let worker = || {
+        loop {
+            let index = {
+                let mut progress = progress.lock().unwrap();
+                loop {
+                    if progress.panicked || progress.finished == len {
+                        return;
+                    }
+                    if let Some(Reverse(index)) = progress.ready.pop() {
+                        break index;
+                    }
+                    progress = condvar.wait(progress).unwrap();
+                }
+            };
+
+            let system = tasks[index].lock().unwrap().take().unwrap();
+            let _guard = PanicGuard {
+                progress: &progress,
+                condvar: &condvar,
+            };
+            let start = Instant::now();
+            // SAFETY: Every system that conflicts with this one is ordered against it, so it has
+            // either finished or will not start until this one has.
+            unsafe { system.run_unsafe(world.with_this_run(first_tick + index as Tick)) };
+            let duration = start.elapsed();
+
+            let mut progress = progress.lock().unwrap();
+            progress.durations[index] = duration;
+            progress.finished += 1;
+            for &dependent in &dependents[index] {
+                progress.remaining[dependent] -= 1;
+                if progress.remaining[dependent] == 0 {
+                    progress.ready.push(Reverse(dependent));
+                }
+            }
+            condvar.notify_all();
+        }
+    };
+
+    pool.broadcast(&worker);
+
+    progress.into_inner().unwrap().durations
+}
@@ -0,0 +1,83 @@
+use std::marker::PhantomData;
+
+use crate::{Access, Component, Entity, SystemParam, UnsafeWorldCell, World};
+
+/// Matches entities that have a `T` component without fetching it.
+pub struct With<T: Component>(PhantomData<T>);
+
+// SAFETY: Nothing is fetched.
+unsafe impl<'w, T: Component> SystemParam<'w> for With<T> {
+    type Item = ();
+
+    fn access(world: &mut World, access: &mut Access) {
+        let id = world.component_id::<T>();
+        access.require_component::<T>(id);
+    }
+
+    unsafe fn fetch(_world: UnsafeWorldCell<'w>, _entity: &Entity) -> Option<Self::Item> {
+        Some(())
+    }
+}
+
+/// Matches entities that do not have a `T` component.
+pub struct Without<T: Component>(PhantomData<T>);
+
+// SAFETY: Nothing is fetched.
+unsafe impl<'w, T: Component> SystemParam<'w> for Without<T> {
+    type Item = ();
+
+    fn access(world: &mut World, access: &mut Access) {
+        let id = world.component_id::<T>();
+        access.exclude_component::<T>(id);
+    }
+
+    unsafe fn fetch(_world: UnsafeWorldCell<'w>, _entity: &Entity) -> Option<Self::Item> {
+        Some(())
+    }
+}
+
+/// Matches entities whose `T` component was added since the system last ran.
+pub struct Added<T: Component>(PhantomData<T>);
+
+// SAFETY: `matches` only reads the ticks of `T`, which `access` registers as a filter read.
+unsafe impl<'w, T: Component> SystemParam<'w> for Added<T> {
+    type Item = ();
+
+    fn access(world: &mut World, access: &mut Access) {
+        let id = world.component_id::<T>();
+        access.require_component::<T>(id);
+        access.add_filter_read(id);
+    }
+
+    unsafe fn matches(world: UnsafeWorldCell<'w>, entity: &Entity) -> bool {
+        unsafe { world.get_component_ticks::<T>(entity) }
+            .is_some_and(|ticks| ticks.is_added(world.last_run()))
+    }
+
+    unsafe fn fetch(_world: UnsafeWorldCell<'w>, _entity: &Entity) -> Option<Self::Item> {
+        Some(())
+    }
+}
+
+/// Matches entities whose `T` component was added or written to since the system last ran.
+pub struct Changed<T: Component>(PhantomData<T>);
+
+// SAFETY: `matches` only reads the ticks of `T`, which `access` registers as a filter read.
+unsafe impl<'w, T: Component> SystemParam<'w> for Changed<T> {
+    type Item = ();
+
+    fn access(world: &mut World, access: &mut Access) {
+        let id = world.component_id::<T>();
+        access.require_component::<T>(id);
+        access.add_filter_read(id);
+    }
+
+    unsafe fn matches(world: UnsafeWorldCell<'w>, entity: &Entity) -> bool {
+        unsafe { world.get_component_ticks::<T>(entity) }
+            .is_some_and(|ticks| ticks.is_changed(world.last_run()))
+    }
+
+    unsafe fn fetch(_world: UnsafeWorldCell<'w>, _entity: &Entity) -> Option<Self::Item> {
+        Some(())
+    }
+}
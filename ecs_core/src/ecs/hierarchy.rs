@@ -0,0 +1,148 @@
+use crate::{Component, EcsError, Entity, World};
+
+/// The parent of an entity. Set with `World::set_parent` so the parent's `Children` stay in sync.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Parent(Entity);
+impl Component for Parent {}
+
+impl Parent {
+    pub fn get(&self) -> &Entity {
+        &self.0
+    }
+}
+
+/// The children of an entity, in the order they were parented. Only present while the entity has
+/// at least one child.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Children(Vec<Entity>);
+impl Component for Children {}
+
+impl Children {
+    pub fn iter(&self) -> impl Iterator<Item = &Entity> {
+        self.0.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl World {
+    /// Makes `child` a child of `parent`, detaching it from its previous parent.
+    ///
+    /// # Panics
+    /// If either entity is not alive, or `parent` is `child` or one of its descendants. See
+    /// `World::try_set_parent` for a non-panicking version.
+    pub fn set_parent(&mut self, child: &Entity, parent: &Entity) {
+        if let Err(e) = self.try_set_parent(child, parent) {
+            panic!("{e}");
+        }
+    }
+
+    /// Makes `child` a child of `parent`, detaching it from its previous parent.
+    ///
+    /// # Returns
+    /// `Result<(), EcsError>` - An error if either entity is not alive, or if `parent` is `child`
+    /// or one of its descendants
+    pub fn try_set_parent(&mut self, child: &Entity, parent: &Entity) -> Result<(), EcsError> {
+        for entity in [child, parent] {
+            if !self.is_alive(entity) {
+                return Err(EcsError::EntityNotFound(entity.clone()));
+            }
+        }
+
+        let mut ancestor = Some(parent.clone());
+        while let Some(entity) = ancestor {
+            if entity == *child {
+                return Err(EcsError::HierarchyCycle {
+                    child: child.clone(),
+                    parent: parent.clone(),
+                });
+            }
+            ancestor = self
+                .get_component::<Parent>(&entity)
+                .map(|parent| parent.0.clone());
+        }
+
+        if self.get_component::<Parent>(child).map(Parent::get) == Some(parent) {
+            return Ok(());
+        }
+
+        self.remove_parent(child);
+        self.add_component(child, Parent(parent.clone()));
+        match self.get_component_mut::<Children>(parent) {
+            Some(children) => children.0.push(child.clone()),
+            None => self.add_component(parent, Children(vec![child.clone()])),
+        }
+
+        Ok(())
+    }
+
+    /// Detaches `child` from its parent, making it a root.
+    ///
+    /// # Returns
+    /// `Option<Entity>` - The previous parent, if any
+    pub fn remove_parent(&mut self, child: &Entity) -> Option<Entity> {
+        let parent = self.remove_component::<Parent>(child)?.0;
+        if let Some(children) = self.get_component_mut::<Children>(&parent) {
+            children.0.retain(|entity| entity != child);
+            if children.0.is_empty() {
+                self.remove_component::<Children>(&parent);
+            }
+        }
+        Some(parent)
+    }
+
+    /// Despawns `entity` along with all of its descendants.
+    ///
+    /// # Panics
+    /// If the entity is not alive. See `World::try_despawn_recursive` for a non-panicking
+    /// version.
+    pub fn despawn_recursive(&mut self, entity: Entity) {
+        if let Err(e) = self.try_despawn_recursive(entity) {
+            panic!("{e}");
+        }
+    }
+
+    /// Despawns `entity` along with all of its descendants.
+    ///
+    /// Descendants that are no longer alive are skipped.
+    ///
+    /// # Returns
+    /// `Result<(), EcsError>` - An error if `entity` itself is not alive
+    pub fn try_despawn_recursive(&mut self, entity: Entity) -> Result<(), EcsError> {
+        if !self.is_alive(&entity) {
+            return Err(EcsError::EntityNotFound(entity));
+        }
+
+        self.remove_parent(&entity);
+        let mut stack = vec![entity];
+        while let Some(entity) = stack.pop() {
+            // A child may already have been despawned without going through the hierarchy.
+            if !self.is_alive(&entity) {
+                continue;
+            }
+            if let Some(children) = self.remove_component::<Children>(&entity) {
+                stack.extend(children.0);
+            }
+            self.try_despawn(entity)?;
+        }
+
+        Ok(())
+    }
+
+    /// Removes `entity` from the hierarchy before it is despawned: it is dropped from its parent's
+    /// `Children`, and its children become roots.
+    pub(crate) fn detach_hierarchy(&mut self, entity: &Entity) {
+        self.remove_parent(entity);
+        if let Some(children) = self.remove_component::<Children>(entity) {
+            for child in children.0 {
+                self.remove_component::<Parent>(&child);
+            }
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use std::sync::Arc;
+
+use crate::{Entity, World};
+
+/// A callback run when a component is added to, inserted on or removed from an entity.
+pub type ComponentHook = Arc<dyn Fn(&mut World, &Entity) + Send + Sync>;
+
+/// The point in a component's lifecycle a hook runs at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookKind {
+    /// After the component is added to an entity that did not have one.
+    Add,
+    /// After the component is added or replaced.
+    Insert,
+    /// Before the component is removed, either directly or by despawning the entity, while it
+    /// can still be read.
+    Remove,
+}
+
+/// The hooks registered for a single component type.
+#[derive(Default, Clone)]
+pub(crate) struct ComponentHooks {
+    on_add: Vec<ComponentHook>,
+    on_insert: Vec<ComponentHook>,
+    on_remove: Vec<ComponentHook>,
+}
+
+impl ComponentHooks {
+    pub(crate) fn get(&self, kind: HookKind) -> &[ComponentHook] {
+        match kind {
+            HookKind::Add => &self.on_add,
+            HookKind::Insert => &self.on_insert,
+            HookKind::Remove => &self.on_remove,
+        }
+    }
+
+    pub(crate) fn push(&mut self, kind: HookKind, hook: ComponentHook) {
+        match kind {
+            HookKind::Add => self.on_add.push(hook),
+            HookKind::Insert => self.on_insert.push(hook),
+            HookKind::Remove => self.on_remove.push(hook),
+        }
+    }
+}
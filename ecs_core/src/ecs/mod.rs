@@ -4,8 +4,71 @@ pub use entity::*;
 mod world;
 pub use world::*;
 
+mod world_cell;
+pub use world_cell::*;
+
 mod component;
 pub use component::*;
 
+mod error;
+pub use error::*;
+
+mod resource;
+pub use resource::*;
+
 mod system;
 pub use system::*;
+
+mod filter;
+pub use filter::*;
+
+mod change_detection;
+pub use change_detection::*;
+
+mod schedule;
+pub use schedule::*;
+
+mod executor;
+pub use executor::*;
+
+mod commands;
+pub use commands::*;
+
+mod event;
+pub use event::*;
+
+mod archetype;
+pub use archetype::*;
+
+mod sparse_set;
+pub(crate) use sparse_set::*;
+
+mod bundle;
+pub use bundle::*;
+
+mod reflect;
+pub use reflect::*;
+
+mod hierarchy;
+pub use hierarchy::*;
+
+mod hooks;
+pub use hooks::*;
+
+mod type_registry;
+pub use type_registry::*;
+
+mod scene;
+pub use scene::*;
+
+mod snapshot;
+pub use snapshot::*;
+
+mod transfer;
+pub(crate) use transfer::*;
+
+mod dynamic;
+pub use dynamic::*;
+
+mod diagnostics;
+pub use diagnostics::*;
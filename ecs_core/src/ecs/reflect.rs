@@ -0,0 +1,44 @@
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{Component, EcsError};
+
+/// A field or whole component converted to a dynamic value.
+pub type ReflectValue = ron::Value;
+
+/// A component whose fields can be read and written by name, for tools such as editors, scenes
+/// and debug views.
+///
+/// Usually implemented with `#[component(reflect)]`. The fields of tuple structs are named by
+/// their index. Whole components are converted through their serde implementations, single
+/// fields through those of the field type. Register the component with `World::register_type`
+/// to access it by name.
+pub trait Reflect: Component + Serialize + DeserializeOwned {
+    /// The name of every field, in declaration order.
+    fn field_names() -> &'static [&'static str];
+
+    /// Reads the field called `name`.
+    ///
+    /// # Returns
+    /// `Result<ReflectValue, EcsError>` - The field, or an error if there is no such field
+    fn field(&self, name: &str) -> Result<ReflectValue, EcsError>;
+
+    /// Overwrites the field called `name` with `value`.
+    ///
+    /// # Returns
+    /// `Result<(), EcsError>` - An error if there is no such field or `value` does not fit it,
+    /// in which case the field is left unchanged
+    fn set_field(&mut self, name: &str, value: ReflectValue) -> Result<(), EcsError>;
+}
+
+/// Converts `value` to a `ReflectValue` through its serde implementation.
+pub fn to_reflect_value<T: Serialize>(value: &T) -> Result<ReflectValue, EcsError> {
+    let ron = ron::to_string(value).map_err(|e| EcsError::Reflect(e.to_string()))?;
+    ron::from_str(&ron).map_err(|e| EcsError::Reflect(e.to_string()))
+}
+
+/// Converts a `ReflectValue` back to a `T` through its serde implementation.
+pub fn from_reflect_value<T: DeserializeOwned>(value: ReflectValue) -> Result<T, EcsError> {
+    value
+        .into_rust::<T>()
+        .map_err(|e| EcsError::Reflect(e.to_string()))
+}
@@ -0,0 +1,144 @@
+use std::{
+    any::{Any, TypeId, type_name},
+    cell::UnsafeCell,
+    collections::HashMap,
+    marker::PhantomData,
+};
+
+use crate::{Access, Entity, SystemParam, UnsafeWorldCell, World};
+
+pub type ResourceId = u32;
+
+/// A global singleton stored in the `World`, such as frame time or input state.
+pub trait Resource: 'static + Send + Sync {}
+
+/// A type-erased resource that can be mutably borrowed through a shared reference, like
+/// `ComponentColumn`.
+struct ResourceData(UnsafeCell<Box<dyn Any + Send + Sync>>);
+
+// SAFETY: The boxed value is `Send + Sync`. Unsynchronised mutable access only happens through
+// `Resources::get_unchecked_mut`, whose callers must guarantee the resource is not borrowed elsewhere.
+unsafe impl Sync for ResourceData {}
+
+/// Storage for every `Resource` of a `World`, indexed by `ResourceId`.
+#[derive(Default)]
+pub struct Resources {
+    lookup_map: HashMap<TypeId, ResourceId>,
+    names: Vec<&'static str>,
+    data: Vec<Option<ResourceData>>,
+}
+
+impl Resources {
+    /// Retrieves the associated id for the type that implements `Resource`, registering it if needed.
+    ///
+    /// # Panics
+    /// If the total number of registered resources exceeds the maximum number that can exist
+    pub fn id<R: Resource>(&mut self) -> ResourceId {
+        if self.data.len() >= ResourceId::MAX as usize {
+            panic!("Exceeding maximum resource types in Resources");
+        }
+
+        *self.lookup_map.entry(TypeId::of::<R>()).or_insert_with(|| {
+            self.names.push(type_name::<R>());
+            self.data.push(None);
+            (self.data.len() - 1) as ResourceId
+        })
+    }
+
+    /// Retrieves the type name a `ResourceId` was registered with.
+    pub fn name(&self, id: ResourceId) -> Option<&'static str> {
+        self.names.get(id as usize).copied()
+    }
+
+    pub fn insert<R: Resource>(&mut self, resource: R) -> Option<R> {
+        let id = self.id::<R>();
+        let old = self.data[id as usize].replace(ResourceData(UnsafeCell::new(Box::new(resource))));
+        old.map(|data| {
+            *data
+                .0
+                .into_inner()
+                .downcast::<R>()
+                .expect("Resource type mismatch")
+        })
+    }
+
+    pub fn remove<R: Resource>(&mut self) -> Option<R> {
+        let id = *self.lookup_map.get(&TypeId::of::<R>())?;
+        let data = self.data[id as usize].take()?;
+        Some(
+            *data
+                .0
+                .into_inner()
+                .downcast::<R>()
+                .expect("Resource type mismatch"),
+        )
+    }
+
+    pub fn contains<R: Resource>(&self) -> bool {
+        self.get::<R>().is_some()
+    }
+
+    pub fn get<R: Resource>(&self) -> Option<&R> {
+        let id = *self.lookup_map.get(&TypeId::of::<R>())?;
+        // SAFETY: Mutable borrows only exist through `get_mut`, which needs `&mut self`, or
+        // `get_unchecked_mut`, whose callers guarantee there are no other borrows.
+        unsafe { &**self.data[id as usize].as_ref()?.0.get() }.downcast_ref::<R>()
+    }
+
+    pub fn get_mut<R: Resource>(&mut self) -> Option<&mut R> {
+        let id = *self.lookup_map.get(&TypeId::of::<R>())?;
+        self.data[id as usize]
+            .as_mut()?
+            .0
+            .get_mut()
+            .downcast_mut::<R>()
+    }
+
+    /// # Safety
+    /// No other reference to the `R` resource may be live for the lifetime of the returned borrow.
+    #[allow(clippy::mut_from_ref)]
+    pub(crate) unsafe fn get_unchecked_mut<R: Resource>(&self) -> Option<&mut R> {
+        let id = *self.lookup_map.get(&TypeId::of::<R>())?;
+        unsafe { &mut **self.data[id as usize].as_ref()?.0.get() }.downcast_mut::<R>()
+    }
+}
+
+/// Borrows the `R` resource immutably. Entities only match while the resource exists.
+pub struct Res<R: Resource>(PhantomData<R>);
+
+// SAFETY: `fetch` only reads the `R` resource, which `access` registers as a resource read.
+unsafe impl<'w, R: Resource> SystemParam<'w> for Res<R> {
+    type Item = &'w R;
+
+    fn access(world: &mut World, access: &mut Access) {
+        access.add_resource_read(world.resource_id::<R>());
+    }
+
+    unsafe fn fetch(world: UnsafeWorldCell<'w>, _entity: &Entity) -> Option<Self::Item> {
+        unsafe { Self::fetch_once(world) }
+    }
+
+    unsafe fn fetch_once(world: UnsafeWorldCell<'w>) -> Option<Self::Item> {
+        unsafe { world.get_resource::<R>() }
+    }
+}
+
+/// Borrows the `R` resource mutably. Entities only match while the resource exists.
+pub struct ResMut<R: Resource>(PhantomData<R>);
+
+// SAFETY: `fetch` only borrows the `R` resource, which `access` registers as a resource write.
+unsafe impl<'w, R: Resource> SystemParam<'w> for ResMut<R> {
+    type Item = &'w mut R;
+
+    fn access(world: &mut World, access: &mut Access) {
+        access.add_resource_write(world.resource_id::<R>());
+    }
+
+    unsafe fn fetch(world: UnsafeWorldCell<'w>, _entity: &Entity) -> Option<Self::Item> {
+        unsafe { Self::fetch_once(world) }
+    }
+
+    unsafe fn fetch_once(world: UnsafeWorldCell<'w>) -> Option<Self::Item> {
+        unsafe { world.get_resource_mut::<R>() }
+    }
+}
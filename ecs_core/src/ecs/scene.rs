@@ -0,0 +1,206 @@
+use std::collections::{BTreeMap, HashMap};
+
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+use crate::{EcsError, Entity, Parent, ReflectValue, World};
+
+/// A snapshot of entities and their registered components that can be written to and read from
+/// RON, e.g. a saved game or a hand-authored starting scene.
+///
+/// ```ron
+/// (
+///     entities: [
+///         (
+///             entity: (id: 0, generation: 0),
+///             components: {
+///                 "Position": (x: 6.0, y: 6.0, z: 0.0),
+///             },
+///         ),
+///         (entity: (id: 1, generation: 0), parent: Some((id: 0, generation: 0))),
+///     ],
+/// )
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Scene {
+    pub entities: Vec<SceneEntity>,
+}
+
+/// An entity of a `Scene`. `entity` only identifies it within the scene, loading always spawns a
+/// new entity for it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SceneEntity {
+    pub entity: Entity,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<Entity>,
+    /// Components keyed by the name they are registered under in the `TypeRegistry`.
+    #[serde(default)]
+    pub components: BTreeMap<String, ReflectValue>,
+}
+
+impl Scene {
+    pub fn from_ron(ron: &str) -> Result<Self, EcsError> {
+        ron::from_str(ron).map_err(|e| EcsError::SceneFormat(e.to_string()))
+    }
+
+    pub fn to_ron(&self) -> Result<String, EcsError> {
+        ron::ser::to_string_pretty(self, PrettyConfig::default())
+            .map_err(|e| EcsError::SceneFormat(e.to_string()))
+    }
+}
+
+/// Maps the entities of a `Scene` to the entities spawned for them by `World::load_scene`, or
+/// entities moved by `World::transfer_entities` to their new handles.
+///
+/// Entities are matched with their generation, so a reference to an entity whose id was reused
+/// before the scene was saved does not end up pointing at the new entity.
+#[derive(Debug, Clone, Default)]
+pub struct EntityMap {
+    entities: HashMap<Entity, Entity>,
+}
+
+impl EntityMap {
+    pub(crate) fn insert(&mut self, entity: Entity, mapped: Entity) {
+        self.entities.insert(entity, mapped);
+    }
+
+    /// The entity spawned for the scene entity `entity`, or the new handle of a moved entity.
+    pub fn get(&self, entity: &Entity) -> Option<&Entity> {
+        self.entities.get(entity)
+    }
+
+    /// Replaces `entity` with the entity spawned or moved for it.
+    ///
+    /// # Returns
+    /// `Result<(), EcsError>` - An error if the scene or transfer did not contain `entity`, which
+    /// is left unchanged
+    pub fn map_entity(&self, entity: &mut Entity) -> Result<(), EcsError> {
+        let mapped = self
+            .get(entity)
+            .ok_or_else(|| EcsError::UnknownSceneEntity(entity.clone()))?;
+        *entity = mapped.clone();
+        Ok(())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Entity, &Entity)> {
+        self.entities.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+}
+
+/// Implemented by components that hold references to other entities, so they can be pointed at
+/// the newly spawned entities when a `Scene` is loaded. Register such components with
+/// `World::register_type_with_entities`.
+pub trait MapEntities {
+    /// Points every entity reference at the entity spawned for it, see `EntityMap::map_entity`.
+    ///
+    /// # Returns
+    /// `Result<(), EcsError>` - An error if a reference points outside the scene, which fails the
+    /// whole load
+    fn map_entities(&mut self, entities: &EntityMap) -> Result<(), EcsError>;
+}
+
+impl World {
+    /// Saves every entity with its registered components and its parent. Components that are not
+    /// registered in the `TypeRegistry` are left out.
+    ///
+    /// # Returns
+    /// `Result<Scene, EcsError>` - The scene, or an error if a component fails to serialize
+    pub fn save_scene(&self) -> Result<Scene, EcsError> {
+        let mut entities: Vec<&Entity> = self
+            .archetypes()
+            .iter()
+            .flat_map(|archetype| archetype.entities())
+            .collect();
+        entities.sort_by_key(|entity| entity.id());
+
+        let mut scene = Scene::default();
+        for entity in entities {
+            let components = self
+                .reflect_entity(entity)?
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value))
+                .collect();
+            scene.entities.push(SceneEntity {
+                entity: entity.clone(),
+                parent: self
+                    .get_component::<Parent>(entity)
+                    .map(|parent| parent.get().clone()),
+                components,
+            });
+        }
+        Ok(scene)
+    }
+
+    /// Spawns a new entity for every entity in `scene`, then restores their components and
+    /// parents. Entity references in components registered with
+    /// `World::register_type_with_entities` are remapped to the new entities.
+    ///
+    /// Nothing is spawned if loading fails.
+    ///
+    /// # Returns
+    /// `Result<EntityMap, EcsError>` - The entities spawned for each scene entity, or an error if
+    /// an entity is duplicated, a parent or remapped entity reference is not in the scene, or a
+    /// component is unknown or fails to deserialize
+    pub fn load_scene(&mut self, scene: &Scene) -> Result<EntityMap, EcsError> {
+        let mut map = EntityMap::default();
+        for scene_entity in &scene.entities {
+            if map.get(&scene_entity.entity).is_some() {
+                self.despawn_scene(&map);
+                return Err(EcsError::DuplicateSceneEntity(scene_entity.entity.clone()));
+            }
+            let entity = self.spawn_entity();
+            map.insert(scene_entity.entity.clone(), entity);
+        }
+
+        if let Err(e) = self.restore_scene(scene, &map) {
+            self.despawn_scene(&map);
+            return Err(e);
+        }
+        Ok(map)
+    }
+
+    fn restore_scene(&mut self, scene: &Scene, map: &EntityMap) -> Result<(), EcsError> {
+        for scene_entity in &scene.entities {
+            let entity = map.entities[&scene_entity.entity].clone();
+            for (name, value) in &scene_entity.components {
+                self.insert_by_name(&entity, name, value.clone())?;
+            }
+        }
+
+        for scene_entity in &scene.entities {
+            if let Some(parent) = &scene_entity.parent {
+                let parent = map
+                    .get(parent)
+                    .ok_or_else(|| EcsError::UnknownSceneEntity(parent.clone()))?
+                    .clone();
+                self.try_set_parent(&map.entities[&scene_entity.entity], &parent)?;
+            }
+        }
+
+        let mappers: Vec<_> = self
+            .type_registry()
+            .iter()
+            .filter_map(|registration| registration.entity_mapper())
+            .collect();
+        for mapper in mappers {
+            for entity in map.entities.values() {
+                mapper(self, entity, map)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn despawn_scene(&mut self, map: &EntityMap) {
+        for entity in map.entities.values() {
+            let _ = self.try_despawn(entity.clone());
+        }
+    }
+}
@@ -0,0 +1,490 @@
+use std::{
+    collections::HashMap,
+    marker::PhantomData,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    Access, EcsError, Entity, ExecutorKind, QueryState, SystemDiagnostics, SystemParam,
+    UnsafeWorldCell, World,
+    ecs::{ThreadPool, executor},
+};
+
+/// The phases of a frame. Every system in a stage runs before any system of a later stage.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Stage {
+    PreUpdate,
+    Update,
+    PostUpdate,
+    RenderExtract,
+}
+
+/// A unit of work that a `Schedule` runs against a `World`.
+pub trait System: Send + 'static {
+    /// Registers everything the system uses with `world` before its first run.
+    ///
+    /// # Returns
+    /// `Access` - The components and resources the system borrows
+    ///
+    /// # Panics
+    /// If the system borrows the same component or resource more than once
+    fn initialize(&mut self, world: &mut World) -> Access;
+
+    /// Whether the system needs the whole `World` to itself, regardless of its `Access`.
+    fn is_exclusive(&self) -> bool {
+        false
+    }
+
+    fn run(&mut self, world: &mut World);
+
+    /// Runs the system through a shared handle to the world, alongside other systems.
+    ///
+    /// # Safety
+    /// `System::initialize` must have been called with this world, and nothing may borrow the
+    /// components or resources in the returned `Access` while the system runs. Exclusive
+    /// systems must be the only thing using the world.
+    unsafe fn run_unsafe(&mut self, world: UnsafeWorldCell<'_>);
+
+    /// The number of entities the system ran for in its last run, for systems that run a query.
+    fn matched_entities(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// A system that runs `f` for every entity matching the query `P`.
+///
+/// Unlike `World::system`, each `QuerySystem` tracks its own last run, so change filters in
+/// two systems with the same query do not interfere with each other.
+pub struct QuerySystem<P, F> {
+    state: QueryState,
+    access: Option<Access>,
+    matched: usize,
+    f: F,
+    marker: PhantomData<fn() -> P>,
+}
+
+impl<P, F> QuerySystem<P, F>
+where
+    for<'w> P: SystemParam<'w> + 'static,
+    for<'w> F: FnMut(Entity, <P as SystemParam<'w>>::Item) + Send + 'static,
+{
+    pub fn new(f: F) -> Self {
+        Self {
+            state: QueryState::default(),
+            access: None,
+            matched: 0,
+            f,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<P, F> System for QuerySystem<P, F>
+where
+    for<'w> P: SystemParam<'w> + 'static,
+    for<'w> F: FnMut(Entity, <P as SystemParam<'w>>::Item) + Send + 'static,
+{
+    fn initialize(&mut self, world: &mut World) -> Access {
+        let access = world.query_access::<P>();
+        self.access = Some(access.clone());
+        access
+    }
+
+    fn run(&mut self, world: &mut World) {
+        self.matched = world.system_with_state::<P, _>(&mut self.state, &mut self.f);
+    }
+
+    unsafe fn run_unsafe(&mut self, world: UnsafeWorldCell<'_>) {
+        let access = self
+            .access
+            .as_ref()
+            .expect("QuerySystem must be initialized before running");
+        self.matched =
+            unsafe { World::system_unchecked::<P, _>(world, &mut self.state, access, &mut self.f) };
+    }
+
+    fn matched_entities(&self) -> Option<usize> {
+        Some(self.matched)
+    }
+}
+
+/// A system that runs `f` once per run for the query `P`, which may only use resources, such as
+/// `Res`, `ResMut`, `Commands` or events. See `World::resource_system`.
+pub struct ResourceSystem<P, F> {
+    state: QueryState,
+    f: F,
+    marker: PhantomData<fn() -> P>,
+}
+
+impl<P, F> ResourceSystem<P, F>
+where
+    for<'w> P: SystemParam<'w> + 'static,
+    for<'w> F: FnMut(<P as SystemParam<'w>>::Item) + Send + 'static,
+{
+    pub fn new(f: F) -> Self {
+        Self {
+            state: QueryState::default(),
+            f,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<P, F> System for ResourceSystem<P, F>
+where
+    for<'w> P: SystemParam<'w> + 'static,
+    for<'w> F: FnMut(<P as SystemParam<'w>>::Item) + Send + 'static,
+{
+    fn initialize(&mut self, world: &mut World) -> Access {
+        world.resource_access::<P>()
+    }
+
+    fn run(&mut self, world: &mut World) {
+        world.resource_system_with_state::<P, _>(&mut self.state, &mut self.f);
+    }
+
+    unsafe fn run_unsafe(&mut self, world: UnsafeWorldCell<'_>) {
+        unsafe { World::resource_system_unchecked::<P, _>(world, &mut self.state, &mut self.f) };
+    }
+}
+
+/// A system with full mutable access to the `World`, for work that does not fit a query.
+pub struct ExclusiveSystem<F> {
+    f: F,
+}
+
+impl<F: FnMut(&mut World) + Send + 'static> ExclusiveSystem<F> {
+    pub fn new(f: F) -> Self {
+        Self { f }
+    }
+}
+
+impl<F: FnMut(&mut World) + Send + 'static> System for ExclusiveSystem<F> {
+    fn initialize(&mut self, _world: &mut World) -> Access {
+        Access::default()
+    }
+
+    fn is_exclusive(&self) -> bool {
+        true
+    }
+
+    fn run(&mut self, world: &mut World) {
+        (self.f)(world);
+    }
+
+    unsafe fn run_unsafe(&mut self, world: UnsafeWorldCell<'_>) {
+        (self.f)(unsafe { world.world_mut() });
+    }
+}
+
+/// A named system and the ordering constraints it was registered with.
+struct SystemNode {
+    name: &'static str,
+    stage: Stage,
+    system: Box<dyn System>,
+    access: Option<Access>,
+    before: Vec<&'static str>,
+    after: Vec<&'static str>,
+    /// The wall time of the last run.
+    duration: Duration,
+}
+
+impl SystemNode {
+    fn run(&mut self, world: &mut World) {
+        let start = Instant::now();
+        self.system.run(world);
+        self.duration = start.elapsed();
+    }
+}
+
+/// Ordering options for a system that was just added to a `Schedule`.
+pub struct SystemConfig<'s> {
+    node: &'s mut SystemNode,
+}
+
+impl SystemConfig<'_> {
+    /// Runs this system before the system called `name`.
+    pub fn before(self, name: &'static str) -> Self {
+        self.node.before.push(name);
+        self
+    }
+
+    /// Runs this system after the system called `name`.
+    pub fn after(self, name: &'static str) -> Self {
+        self.node.after.push(name);
+        self
+    }
+}
+
+/// Named systems grouped into `Stage`s, run in order against a `World`.
+///
+/// Within a stage, systems run in the order they were added unless `before`/`after`
+/// constraints say otherwise.
+#[derive(Default)]
+pub struct Schedule {
+    systems: Vec<SystemNode>,
+    order: Option<Vec<usize>>,
+    executor: ExecutorKind,
+    /// Started by the first multi-threaded run.
+    pool: Option<ThreadPool>,
+}
+
+impl Schedule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Changes how the systems are run. Either executor produces the same results.
+    pub fn set_executor(&mut self, executor: ExecutorKind) {
+        self.executor = executor;
+    }
+
+    /// Adds `system` to `stage` under `name`, which other systems use to order against it.
+    pub fn add_system(
+        &mut self,
+        name: &'static str,
+        stage: Stage,
+        system: impl System,
+    ) -> SystemConfig<'_> {
+        self.order = None;
+        self.systems.push(SystemNode {
+            name,
+            stage,
+            system: Box::new(system),
+            access: None,
+            before: vec![],
+            after: vec![],
+            duration: Duration::ZERO,
+        });
+        SystemConfig {
+            node: self.systems.last_mut().unwrap(),
+        }
+    }
+
+    /// The names of the systems in the order they will run.
+    ///
+    /// # Returns
+    /// `Result<Vec<&'static str>, EcsError>` - Or why the constraints cannot be satisfied
+    pub fn system_order(&mut self) -> Result<Vec<&'static str>, EcsError> {
+        self.build()?;
+        Ok(self.ordered().map(|node| node.name).collect())
+    }
+
+    /// Resolves the run order of every system, checking that all ordering constraints
+    /// refer to existing systems and can be satisfied.
+    pub fn build(&mut self) -> Result<(), EcsError> {
+        if self.order.is_some() {
+            return Ok(());
+        }
+
+        let mut indices = HashMap::new();
+        for (index, node) in self.systems.iter().enumerate() {
+            if indices.insert(node.name, index).is_some() {
+                return Err(EcsError::DuplicateSystem(node.name));
+            }
+        }
+
+        // `edges[a]` holds every system that has to run after `a` within the same stage.
+        let mut edges = vec![vec![]; self.systems.len()];
+        for (index, node) in self.systems.iter().enumerate() {
+            let constraints = node
+                .before
+                .iter()
+                .map(|name| (name, true))
+                .chain(node.after.iter().map(|name| (name, false)));
+
+            for (name, before) in constraints {
+                let Some(&other) = indices.get(name) else {
+                    return Err(EcsError::UnknownSystem {
+                        system: node.name,
+                        dependency: name,
+                    });
+                };
+                let (first, then) = if before {
+                    (index, other)
+                } else {
+                    (other, index)
+                };
+
+                let (first_stage, then_stage) =
+                    (self.systems[first].stage, self.systems[then].stage);
+                if first_stage > then_stage {
+                    return Err(EcsError::StageOrderConflict {
+                        before: self.systems[first].name,
+                        after: self.systems[then].name,
+                    });
+                }
+                if first_stage == then_stage {
+                    edges[first].push(then);
+                }
+            }
+        }
+
+        let mut in_degree = vec![0; self.systems.len()];
+        for &then in edges.iter().flatten() {
+            in_degree[then] += 1;
+        }
+
+        // Kahn's algorithm, always picking the earliest added system that is ready so the
+        // order is stable when there are no constraints.
+        let mut order = Vec::with_capacity(self.systems.len());
+        let mut stages: Vec<Stage> = self.systems.iter().map(|node| node.stage).collect();
+        stages.sort();
+        stages.dedup();
+
+        for stage in stages {
+            let mut ready: Vec<usize> = (0..self.systems.len())
+                .filter(|&index| self.systems[index].stage == stage && in_degree[index] == 0)
+                .collect();
+            let stage_len = self
+                .systems
+                .iter()
+                .filter(|node| node.stage == stage)
+                .count();
+            let start = order.len();
+
+            while !ready.is_empty() {
+                let next = ready.remove(0);
+                order.push(next);
+                for &then in &edges[next] {
+                    in_degree[then] -= 1;
+                    if in_degree[then] == 0 {
+                        let at = ready.partition_point(|&index| index < then);
+                        ready.insert(at, then);
+                    }
+                }
+            }
+
+            if order.len() - start != stage_len {
+                let cycle = (0..self.systems.len())
+                    .filter(|&index| self.systems[index].stage == stage && in_degree[index] > 0)
+                    .map(|index| self.systems[index].name)
+                    .collect();
+                return Err(EcsError::SystemCycle(cycle));
+            }
+        }
+
+        self.order = Some(order);
+        Ok(())
+    }
+
+    /// Runs every system once, initializing systems that have not run before.
+    ///
+    /// # Panics
+    /// If the ordering constraints cannot be satisfied, see `Schedule::build`, or if a system
+    /// borrows the same component or resource more than once
+    pub fn run(&mut self, world: &mut World) {
+        if let Err(e) = self.build() {
+            panic!("{e}");
+        }
+
+        let Some(order) = self.order.clone() else {
+            return;
+        };
+        for &index in &order {
+            let node = &mut self.systems[index];
+            if node.access.is_none() {
+                node.access = Some(node.system.initialize(world));
+            }
+        }
+
+        match self.executor {
+            ExecutorKind::SingleThreaded => {
+                for &index in &order {
+                    self.systems[index].run(world);
+                }
+            }
+            ExecutorKind::MultiThreaded => {
+                // Exclusive systems and `Commands` need sync points, which split the run into
+                // batches that are run in parallel.
+                let mut batch = vec![];
+                for &index in &order {
+                    let node = &self.systems[index];
+                    if node.system.is_exclusive() {
+                        self.run_parallel(world, &batch);
+                        batch.clear();
+                        self.systems[index].run(world);
+                    } else if node.access.as_ref().is_some_and(Access::is_deferred) {
+                        batch.push(index);
+                        self.run_parallel(world, &batch);
+                        batch.clear();
+                    } else {
+                        batch.push(index);
+                    }
+                }
+                self.run_parallel(world, &batch);
+            }
+        }
+    }
+
+    /// Runs a batch of initialized, non-exclusive systems on worker threads, ordering every
+    /// pair that conflicts, is constrained, or is in different stages as in a sequential run.
+    fn run_parallel(&mut self, world: &mut World, batch: &[usize]) {
+        if batch.is_empty() {
+            return;
+        }
+
+        let dependencies: Vec<Vec<usize>> = batch
+            .iter()
+            .enumerate()
+            .map(|(position, &index)| {
+                let node = &self.systems[index];
+                batch[..position]
+                    .iter()
+                    .enumerate()
+                    .filter(|&(_, &earlier)| self.must_precede(earlier, node))
+                    .map(|(earlier_position, _)| earlier_position)
+                    .collect()
+            })
+            .collect();
+
+        let pool = self.pool.get_or_insert_with(ThreadPool::new);
+        let mut nodes: Vec<Option<&mut SystemNode>> = self.systems.iter_mut().map(Some).collect();
+        let systems = batch
+            .iter()
+            .map(|&index| &mut *nodes[index].take().unwrap().system)
+            .collect();
+
+        // SAFETY: Every system was initialized by `Schedule::run`, exclusive systems are never
+        // part of a batch, and conflicting systems are ordered by `must_precede`.
+        let durations = unsafe { executor::run_parallel(pool, world, systems, &dependencies) };
+        for (&index, duration) in batch.iter().zip(durations) {
+            self.systems[index].duration = duration;
+        }
+        world.apply_commands();
+    }
+
+    /// The wall time and matched entity count of each system's last run, in run order.
+    ///
+    /// Systems that have not run yet report a duration of zero.
+    pub fn diagnostics(&self) -> Vec<SystemDiagnostics> {
+        self.ordered()
+            .map(|node| SystemDiagnostics {
+                name: node.name,
+                stage: node.stage,
+                duration: node.duration,
+                matched_entities: node.system.matched_entities(),
+            })
+            .collect()
+    }
+
+    /// Whether the system at `earlier` has to finish before `node` starts in a parallel run.
+    fn must_precede(&self, earlier: usize, node: &SystemNode) -> bool {
+        let earlier = &self.systems[earlier];
+        let (Some(earlier_access), Some(access)) = (&earlier.access, &node.access) else {
+            return true;
+        };
+
+        earlier.stage != node.stage
+            || earlier.before.contains(&node.name)
+            || node.after.contains(&earlier.name)
+            || !earlier_access.is_compatible(access)
+    }
+
+    fn ordered(&self) -> impl Iterator<Item = &SystemNode> {
+        self.order
+            .iter()
+            .flatten()
+            .map(|&index| &self.systems[index])
+    }
+}
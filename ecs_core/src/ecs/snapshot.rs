@@ -0,0 +1,137 @@
+use std::fmt;
+
+use crate::{
+    Component, ComponentId, ComponentList, ComponentTicks, EcsError, Entity, EntityLocation,
+    EntityRegistry, Tick,
+    ecs::{ComponentListOps, ComponentRegistry},
+};
+
+/// Clones and compares the components of one type, see `World::register_snapshot`.
+#[derive(Clone, Copy)]
+pub(crate) struct SnapshotFns {
+    clone: fn(&dyn ComponentListOps) -> Box<dyn ComponentListOps>,
+    eq: fn(&dyn ComponentListOps, &dyn ComponentListOps) -> bool,
+}
+
+impl SnapshotFns {
+    pub(crate) fn of<T: Component + Clone + PartialEq>() -> Self {
+        Self {
+            clone: |list| {
+                let list = downcast::<T>(list);
+                Box::new(ComponentList {
+                    components: list.components.clone(),
+                    ticks: list.ticks.clone(),
+                })
+            },
+            eq: |a, b| downcast::<T>(a).components == downcast::<T>(b).components,
+        }
+    }
+}
+
+fn downcast<T: Component>(list: &dyn ComponentListOps) -> &ComponentList<T> {
+    list.as_any()
+        .downcast_ref::<ComponentList<T>>()
+        .expect("Component type mismatch")
+}
+
+/// A copy of a whole `World` except its resources, taken with `World::snapshot` and put back
+/// with `World::restore`.
+///
+/// Snapshots compare equal when their entities, generations and component values are equal.
+/// Change ticks are ignored, so a replay that reaches the same state compares equal to the
+/// original run.
+#[derive(Debug, PartialEq)]
+pub struct WorldSnapshot {
+    pub(crate) entity_registry: EntityRegistry,
+    pub(crate) locations: Vec<Option<EntityLocation>>,
+    pub(crate) archetypes: Vec<ArchetypeSnapshot>,
+    pub(crate) sparse_sets: Vec<Option<SparseSetSnapshot>>,
+}
+
+impl WorldSnapshot {
+    /// The number of live entities in the snapshot.
+    pub fn entity_count(&self) -> usize {
+        self.archetypes
+            .iter()
+            .map(|archetype| archetype.entities.len())
+            .sum()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct ArchetypeSnapshot {
+    pub(crate) component_ids: Vec<ComponentId>,
+    pub(crate) entities: Vec<Entity>,
+    pub(crate) columns: Vec<ColumnSnapshot>,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct SparseSetSnapshot {
+    pub(crate) column: ColumnSnapshot,
+    pub(crate) entities: Vec<Entity>,
+    pub(crate) sparse: Vec<Option<usize>>,
+}
+
+/// The components of one column or sparse set.
+pub(crate) struct ColumnSnapshot {
+    list: Box<dyn ComponentListOps>,
+    fns: Option<SnapshotFns>,
+}
+
+impl ColumnSnapshot {
+    /// Clones `list`, which holds the components of `id`.
+    ///
+    /// # Returns
+    /// `Result<Self, EcsError>` - An error if the list is not empty and its component type was
+    /// not registered with `World::register_snapshot`
+    pub(crate) fn capture(
+        list: &dyn ComponentListOps,
+        id: ComponentId,
+        registry: &ComponentRegistry,
+    ) -> Result<Self, EcsError> {
+        match registry.snapshot_fns(id) {
+            Some(fns) => Ok(Self {
+                list: (fns.clone)(list),
+                fns: Some(fns),
+            }),
+            // Columns of unregistered types can still be restored as long as they are empty.
+            None if list.is_empty() => Ok(Self {
+                list: list.empty(),
+                fns: None,
+            }),
+            None => Err(EcsError::NotSnapshottable(
+                registry.name(id).unwrap_or("unknown"),
+            )),
+        }
+    }
+
+    /// A copy of the captured list, with every component marked as added and changed at `tick`.
+    pub(crate) fn restore(&self, tick: Tick) -> Box<dyn ComponentListOps> {
+        let mut list = match self.fns {
+            Some(fns) => (fns.clone)(&*self.list),
+            None => self.list.empty(),
+        };
+        list.ticks_mut().fill(ComponentTicks::new(tick));
+        list
+    }
+}
+
+impl PartialEq for ColumnSnapshot {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.fns, other.fns) {
+            (Some(fns), Some(_)) => {
+                self.list.as_any().type_id() == other.list.as_any().type_id()
+                    && (fns.eq)(&*self.list, &*other.list)
+            }
+            _ => self.list.is_empty() && other.list.is_empty(),
+        }
+    }
+}
+
+impl fmt::Debug for ColumnSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ColumnSnapshot")
+            .field("len", &self.list.len())
+            .finish()
+    }
+}
@@ -0,0 +1,129 @@
+use crate::{
+    Component, ComponentId, ComponentList, EcsError, Entity, Tick,
+    ecs::{
+        ColumnSnapshot, ComponentColumn, ComponentListOps, ComponentRegistry, SparseSetSnapshot,
+    },
+};
+
+/// Storage for a single `StorageType::SparseSet` component type.
+///
+/// Components are packed densely in a column, and `sparse` maps entity ids to their row, so adding
+/// or removing one never moves the entity to another archetype.
+pub(crate) struct SparseSet {
+    column: ComponentColumn,
+    entities: Vec<Entity>,
+    sparse: Vec<Option<usize>>,
+}
+
+impl SparseSet {
+    pub(crate) fn new(list: Box<dyn ComponentListOps>) -> Self {
+        Self {
+            column: ComponentColumn::new(list),
+            entities: vec![],
+            sparse: vec![],
+        }
+    }
+
+    pub(crate) fn column(&self) -> &ComponentColumn {
+        &self.column
+    }
+
+    pub(crate) fn column_mut(&mut self) -> &mut ComponentColumn {
+        &mut self.column
+    }
+
+    /// The entities that have a component in this set, indexed by row.
+    pub(crate) fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    /// The row of the component of `entity`, or `None` if it does not have one.
+    pub(crate) fn row(&self, entity: &Entity) -> Option<usize> {
+        let row = (*self.sparse.get(entity.id() as usize)?)?;
+        (self.entities[row] == *entity).then_some(row)
+    }
+
+    /// Records that the component of `entity` was pushed onto the end of the column.
+    pub(crate) fn push_entity(&mut self, entity: Entity) {
+        let index = entity.id() as usize;
+        if index >= self.sparse.len() {
+            self.sparse.resize(index + 1, None);
+        }
+        self.sparse[index] = Some(self.entities.len());
+        self.entities.push(entity);
+    }
+
+    /// Drops the component of `entity`.
+    ///
+    /// # Returns
+    /// `bool` - Whether the entity had one
+    pub(crate) fn remove(&mut self, entity: &Entity) -> bool {
+        let Some(row) = self.row(entity) else {
+            return false;
+        };
+        self.column.get_mut().swap_remove(row);
+        self.remove_entity(row);
+        true
+    }
+
+    /// Moves the component of `entity` into a list of its own.
+    pub(crate) fn take_row(&mut self, entity: &Entity) -> Option<Box<dyn ComponentListOps>> {
+        let row = self.row(entity)?;
+        let mut list = self.column.get().empty();
+        self.column.get_mut().move_row(row, &mut *list);
+        self.remove_entity(row);
+        Some(list)
+    }
+
+    /// Removes the component of `entity` and returns it.
+    ///
+    /// # Panics
+    /// If the set does not hold `T` components
+    pub(crate) fn take<T: Component>(&mut self, entity: &Entity) -> Option<T> {
+        let row = self.row(entity)?;
+        let list = self
+            .column
+            .get_mut()
+            .as_any_mut()
+            .downcast_mut::<ComponentList<T>>()
+            .expect("Component type mismatch");
+        list.ticks.swap_remove(row);
+        let component = list.components.swap_remove(row);
+        self.remove_entity(row);
+        Some(component)
+    }
+
+    pub(crate) fn snapshot(
+        &self,
+        id: ComponentId,
+        registry: &ComponentRegistry,
+    ) -> Result<SparseSetSnapshot, EcsError> {
+        Ok(SparseSetSnapshot {
+            column: ColumnSnapshot::capture(self.column.get(), id, registry)?,
+            entities: self.entities.clone(),
+            sparse: self.sparse.clone(),
+        })
+    }
+
+    /// Replaces every component with those of `snapshot`, stamping them with `tick`.
+    pub(crate) fn restore(&mut self, snapshot: &SparseSetSnapshot, tick: Tick) {
+        self.column = ComponentColumn::new(snapshot.column.restore(tick));
+        self.entities = snapshot.entities.clone();
+        self.sparse = snapshot.sparse.clone();
+    }
+
+    /// Drops every component.
+    pub(crate) fn clear(&mut self) {
+        self.column = ComponentColumn::new(self.column.get().empty());
+        self.entities.clear();
+        self.sparse.clear();
+    }
+
+    fn remove_entity(&mut self, row: usize) {
+        let entity = self.entities.swap_remove(row);
+        self.sparse[entity.id() as usize] = None;
+        if let Some(swapped) = self.entities.get(row) {
+            self.sparse[swapped.id() as usize] = Some(row);
+        }
+    }
+}
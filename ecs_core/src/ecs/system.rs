@@ -1,39 +1,355 @@
-use crate::{Component, Entity, World};
+use crate::{Component, ComponentId, Entity, Mut, ResourceId, StorageType, UnsafeWorldCell, World};
 
-pub trait SystemParam<'w> {
+/// The components a `SystemParam` needs in order to match an `Entity`, and how it borrows them.
+///
+/// Used by `World::system` to select only the archetypes that can satisfy a query, and to reject
+/// queries that would hand out aliasing borrows of the same component.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Access {
+    required: Vec<ComponentId>,
+    excluded: Vec<ComponentId>,
+    sparse_required: Vec<ComponentId>,
+    sparse_excluded: Vec<ComponentId>,
+    filter_reads: Vec<ComponentId>,
+    reads: Vec<ComponentId>,
+    writes: Vec<ComponentId>,
+    conflicts: Vec<ComponentId>,
+    resource_reads: Vec<ResourceId>,
+    resource_writes: Vec<ResourceId>,
+    resource_conflicts: Vec<ResourceId>,
+    deferred: bool,
+}
+
+impl Access {
+    /// Marks the component as required for an entity to match.
+    pub fn require(&mut self, id: ComponentId) {
+        insert_sorted(&mut self.required, id);
+    }
+
+    /// Marks the component as excluded, entities that have it never match.
+    pub fn exclude(&mut self, id: ComponentId) {
+        insert_sorted(&mut self.excluded, id);
+    }
+
+    /// Marks the `T` component as required for an entity to match, depending on how it is stored.
+    ///
+    /// Sparse set components are not part of any archetype, so they are checked per entity.
+    pub fn require_component<T: Component>(&mut self, id: ComponentId) {
+        self.require_stored(id, T::STORAGE_TYPE);
+    }
+
+    /// Marks the `T` component as excluded, depending on how it is stored.
+    pub fn exclude_component<T: Component>(&mut self, id: ComponentId) {
+        self.exclude_stored(id, T::STORAGE_TYPE);
+    }
+
+    /// Marks the component as required for an entity to match, for components whose type is
+    /// only known at runtime.
+    pub fn require_stored(&mut self, id: ComponentId, storage_type: StorageType) {
+        match storage_type {
+            StorageType::Table => self.require(id),
+            StorageType::SparseSet => {
+                insert_sorted(&mut self.sparse_required, id);
+            }
+        }
+    }
+
+    /// Marks the component as excluded, for components whose type is only known at runtime.
+    pub fn exclude_stored(&mut self, id: ComponentId, storage_type: StorageType) {
+        match storage_type {
+            StorageType::Table => self.exclude(id),
+            StorageType::SparseSet => {
+                insert_sorted(&mut self.sparse_excluded, id);
+            }
+        }
+    }
+
+    /// Records that a filter reads the change ticks of the component.
+    ///
+    /// Never conflicts with borrows in the same query, since filters run before anything is fetched.
+    pub fn add_filter_read(&mut self, id: ComponentId) {
+        insert_sorted(&mut self.filter_reads, id);
+    }
+
+    /// Records a shared borrow of the component. Conflicts with a mutable borrow of the same component.
+    pub fn add_read(&mut self, id: ComponentId) {
+        insert_sorted(&mut self.reads, id);
+        if self.writes.binary_search(&id).is_ok() {
+            insert_sorted(&mut self.conflicts, id);
+        }
+    }
+
+    /// Records a mutable borrow of the component. Borrowing it again in the same query is a conflict.
+    pub fn add_write(&mut self, id: ComponentId) {
+        if !insert_sorted(&mut self.writes, id) || self.reads.binary_search(&id).is_ok() {
+            insert_sorted(&mut self.conflicts, id);
+        }
+    }
+
+    /// Records a shared borrow of the resource. Conflicts with a mutable borrow of the same resource.
+    pub fn add_resource_read(&mut self, id: ResourceId) {
+        insert_sorted(&mut self.resource_reads, id);
+        if self.resource_writes.binary_search(&id).is_ok() {
+            insert_sorted(&mut self.resource_conflicts, id);
+        }
+    }
+
+    /// Records a mutable borrow of the resource. Borrowing it again in the same query is a conflict.
+    pub fn add_resource_write(&mut self, id: ResourceId) {
+        if !insert_sorted(&mut self.resource_writes, id)
+            || self.resource_reads.binary_search(&id).is_ok()
+        {
+            insert_sorted(&mut self.resource_conflicts, id);
+        }
+    }
+
+    /// Records that the query defers work, such as `Commands`, that has to be applied to the
+    /// `World` once it has finished.
+    pub fn add_deferred(&mut self) {
+        self.deferred = true;
+    }
+
+    /// The sorted, de-duplicated ids of every required component.
+    pub fn required(&self) -> &[ComponentId] {
+        &self.required
+    }
+
+    /// The sorted, de-duplicated ids of every excluded component.
+    pub fn excluded(&self) -> &[ComponentId] {
+        &self.excluded
+    }
+
+    /// The sorted ids of every required sparse set component.
+    pub fn sparse_required(&self) -> &[ComponentId] {
+        &self.sparse_required
+    }
+
+    /// The sorted ids of every excluded sparse set component.
+    pub fn sparse_excluded(&self) -> &[ComponentId] {
+        &self.sparse_excluded
+    }
+
+    /// The ids of every component whose change ticks are read by a filter.
+    pub fn filter_reads(&self) -> &[ComponentId] {
+        &self.filter_reads
+    }
+
+    /// The ids of every component that is borrowed immutably.
+    pub fn reads(&self) -> &[ComponentId] {
+        &self.reads
+    }
+
+    /// The ids of every component that is mutably borrowed.
+    pub fn writes(&self) -> &[ComponentId] {
+        &self.writes
+    }
+
+    /// The ids of every component that is borrowed in a way that would alias.
+    pub fn conflicts(&self) -> &[ComponentId] {
+        &self.conflicts
+    }
+
+    /// The ids of every resource that is borrowed immutably.
+    pub fn resource_reads(&self) -> &[ResourceId] {
+        &self.resource_reads
+    }
+
+    /// The ids of every resource that is mutably borrowed.
+    pub fn resource_writes(&self) -> &[ResourceId] {
+        &self.resource_writes
+    }
+
+    /// The ids of every resource that is borrowed in a way that would alias.
+    pub fn resource_conflicts(&self) -> &[ResourceId] {
+        &self.resource_conflicts
+    }
+
+    /// Whether the query uses any component, either by borrowing it or to match entities.
+    /// Queries that use none only depend on resources, see `World::resource_system`.
+    pub fn uses_components(&self) -> bool {
+        !self.required.is_empty()
+            || !self.excluded.is_empty()
+            || !self.sparse_required.is_empty()
+            || !self.sparse_excluded.is_empty()
+            || !self.filter_reads.is_empty()
+            || !self.reads.is_empty()
+            || !self.writes.is_empty()
+    }
+
+    /// Whether the query defers work that has to be applied once it has finished.
+    pub fn is_deferred(&self) -> bool {
+        self.deferred
+    }
+
+    /// Whether the query never mutates any component or resource.
+    pub fn is_read_only(&self) -> bool {
+        self.writes.is_empty() && self.resource_writes.is_empty()
+    }
+
+    /// Whether two queries can run at the same time, i.e. neither mutates a component or
+    /// resource the other one uses.
+    pub fn is_compatible(&self, other: &Access) -> bool {
+        let overlaps = |a: &[u32], b: &[u32]| a.iter().any(|id| b.binary_search(id).is_ok());
+        let writes_used_by = |a: &Access, b: &Access| {
+            overlaps(&a.writes, &b.reads)
+                || overlaps(&a.writes, &b.writes)
+                || overlaps(&a.writes, &b.filter_reads)
+                || overlaps(&a.resource_writes, &b.resource_reads)
+                || overlaps(&a.resource_writes, &b.resource_writes)
+        };
+
+        !writes_used_by(self, other) && !writes_used_by(other, self)
+    }
+
+    /// Whether an archetype with the sorted component ids `key` can satisfy the query.
+    pub fn matches_archetype(&self, key: &[ComponentId]) -> bool {
+        self.required.iter().all(|id| key.binary_search(id).is_ok())
+            && !self.excluded.iter().any(|id| key.binary_search(id).is_ok())
+    }
+}
+
+/// Inserts `id` into the sorted `ids`, returning `false` if it was already present.
+fn insert_sorted(ids: &mut Vec<u32>, id: u32) -> bool {
+    match ids.binary_search(&id) {
+        Ok(_) => false,
+        Err(index) => {
+            ids.insert(index, id);
+            true
+        }
+    }
+}
+
+/// A value a system asks for, fetched from the `World` for each entity it runs on, or once per
+/// run for parameters that only use resources.
+///
+/// # Safety
+/// `SystemParam::access` must register every component and resource `SystemParam::fetch` and
+/// `SystemParam::fetch_once` borrow, as a read or, if the borrow is mutable, as a write, and every
+/// component `SystemParam::matches` reads as a filter read. `World::system` only checks the
+/// `Access` for aliasing borrows, so fetching anything it does not cover is undefined behavior.
+pub unsafe trait SystemParam<'w> {
     type Item;
 
-    fn fetch(world: &'w mut World, entity: &Entity) -> Option<Self::Item>;
+    /// Registers the components this parameter needs, and how it borrows them, with `access`.
+    fn access(world: &mut World, access: &mut Access);
+
+    /// Whether `entity` passes the filters of this parameter. Runs before anything is fetched.
+    ///
+    /// # Safety
+    /// No mutable borrow of a component read by `Access::filter_reads` may be live.
+    unsafe fn matches(_world: UnsafeWorldCell<'w>, _entity: &Entity) -> bool {
+        true
+    }
+
+    /// Fetches the parameter for a single entity.
+    ///
+    /// # Safety
+    /// The `Access` produced by `SystemParam::access` must have no conflicts, and no other borrow
+    /// of the components it writes may be live for `'w`.
+    unsafe fn fetch(world: UnsafeWorldCell<'w>, entity: &Entity) -> Option<Self::Item>;
+
+    /// Fetches the parameter once for a whole system run, for parameters that only use
+    /// resources, such as `Res` or `Commands`. See `World::resource_system`.
+    ///
+    /// # Returns
+    /// `Option<Self::Item>` - Or `None` if the parameter needs an entity, or a resource it
+    /// borrows does not exist
+    ///
+    /// # Safety
+    /// Same as `SystemParam::fetch`.
+    unsafe fn fetch_once(_world: UnsafeWorldCell<'w>) -> Option<Self::Item> {
+        None
+    }
 }
 
-impl<'w, T: Component> SystemParam<'w> for T {
-    type Item = &'w mut T;
+// SAFETY: `fetch` only reads the `T` component, which `access` registers as a read.
+unsafe impl<'w, T: Component> SystemParam<'w> for &T {
+    type Item = &'w T;
 
-    fn fetch(world: &'w mut World, entity: &Entity) -> Option<Self::Item> {
-        world.get_component_mut::<T>(entity)
+    fn access(world: &mut World, access: &mut Access) {
+        let id = world.component_id::<T>();
+        access.require_component::<T>(id);
+        access.add_read(id);
+    }
+
+    unsafe fn fetch(world: UnsafeWorldCell<'w>, entity: &Entity) -> Option<Self::Item> {
+        unsafe { world.get_component::<T>(entity) }
     }
 }
 
-impl<'w, T: Component> SystemParam<'w> for Option<T> {
-    type Item = Option<&'w mut T>;
+// SAFETY: `fetch` only borrows the `T` component, which `access` registers as a write.
+unsafe impl<'w, T: Component> SystemParam<'w> for &mut T {
+    type Item = Mut<'w, T>;
+
+    fn access(world: &mut World, access: &mut Access) {
+        let id = world.component_id::<T>();
+        access.require_component::<T>(id);
+        access.add_write(id);
+    }
 
-    fn fetch(world: &'w mut World, entity: &Entity) -> Option<Self::Item> {
-        Some(world.get_component_mut::<T>(entity))
+    unsafe fn fetch(world: UnsafeWorldCell<'w>, entity: &Entity) -> Option<Self::Item> {
+        unsafe { world.get_component_mut::<T>(entity) }
+    }
+}
+
+// SAFETY: `fetch` only reads the `T` component, which `access` registers as a read.
+unsafe impl<'w, T: Component> SystemParam<'w> for Option<&T> {
+    type Item = Option<&'w T>;
+
+    fn access(world: &mut World, access: &mut Access) {
+        access.add_read(world.component_id::<T>());
+    }
+
+    unsafe fn fetch(world: UnsafeWorldCell<'w>, entity: &Entity) -> Option<Self::Item> {
+        Some(unsafe { world.get_component::<T>(entity) })
+    }
+}
+
+// SAFETY: `fetch` only borrows the `T` component, which `access` registers as a write.
+unsafe impl<'w, T: Component> SystemParam<'w> for Option<&mut T> {
+    type Item = Option<Mut<'w, T>>;
+
+    fn access(world: &mut World, access: &mut Access) {
+        access.add_write(world.component_id::<T>());
+    }
+
+    unsafe fn fetch(world: UnsafeWorldCell<'w>, entity: &Entity) -> Option<Self::Item> {
+        Some(unsafe { world.get_component_mut::<T>(entity) })
     }
 }
 
 macro_rules! impl_system_param_tuple {
     ($($name:ident),+) => {
-        impl<'w, $($name),+> SystemParam<'w> for ($($name,)+)
+        // SAFETY: Every element registers what it fetches, so together they cover the tuple.
+        unsafe impl<'w, $($name),+> SystemParam<'w> for ($($name,)+)
         where
             $($name: SystemParam<'w>,)+
         {
             type Item = ($($name::Item,)+);
 
-            fn fetch(world: &'w mut World, entity: &Entity) -> Option<Self::Item> {
-                let world_ptr: *mut World = world;
+            fn access(world: &mut World, access: &mut Access) {
+                $($name::access(world, access);)+
+            }
+
+            unsafe fn matches(world: UnsafeWorldCell<'w>, entity: &Entity) -> bool {
+                unsafe { $($name::matches(world, entity))&&+ }
+            }
+
+            #[allow(non_snake_case)]
+            unsafe fn fetch(world: UnsafeWorldCell<'w>, entity: &Entity) -> Option<Self::Item> {
+                // SAFETY: The caller validated the combined access of every element, so each
+                // element borrows a different component.
+                unsafe {
+                    $(let $name = $name::fetch(world, entity)?;)+
+                    Some(($($name,)+))
+                }
+            }
+
+            #[allow(non_snake_case)]
+            unsafe fn fetch_once(world: UnsafeWorldCell<'w>) -> Option<Self::Item> {
+                // SAFETY: Same as `fetch`.
                 unsafe {
-                    $(let $name = $name::fetch(&mut *world_ptr, entity)?;)+
+                    $(let $name = $name::fetch_once(world)?;)+
                     Some(($($name,)+))
                 }
             }
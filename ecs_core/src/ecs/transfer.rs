@@ -0,0 +1,116 @@
+use crate::{Children, ComponentId, EcsError, Entity, EntityMap, World, ecs::ComponentListOps};
+
+/// A component taken out of a `World`, alone in a list of its type so it can be moved into the
+/// matching column of another `World` without knowing its type.
+pub(crate) struct TakenComponent {
+    /// The id of the component in the `World` it was taken from.
+    pub(crate) id: ComponentId,
+    pub(crate) list: Box<dyn ComponentListOps>,
+}
+
+impl World {
+    /// Moves `entity` and all of its components into `to`, where it is spawned as a new entity.
+    ///
+    /// The entity is detached from its parent and children, see `World::transfer_entities` to
+    /// move a whole hierarchy.
+    ///
+    /// # Arguments
+    /// - `entity` The entity to move
+    /// - `to` The `World` to move it into
+    ///
+    /// # Returns
+    /// `Result<Entity, EcsError>` - The entity in `to`, or an error if the entity is not alive or
+    /// a remove hook despawned it
+    pub fn transfer_entity(&mut self, entity: &Entity, to: &mut World) -> Result<Entity, EcsError> {
+        let map = self.transfer_entities(std::slice::from_ref(entity), to)?;
+        map.get(entity)
+            .cloned()
+            .ok_or_else(|| EcsError::EntityNotFound(entity.clone()))
+    }
+
+    /// Moves `entities` and all of their components into `to`, where each is spawned as a new
+    /// entity.
+    ///
+    /// Parents and children that are moved together keep their hierarchy in `to`; links to
+    /// entities left behind are removed. Entity references in components registered with
+    /// `World::register_type_with_entities` in `to` are remapped to the moved entities. Remove
+    /// hooks run in this `World` and add and insert hooks in `to`, as if the entities were
+    /// despawned and spawned.
+    ///
+    /// # Arguments
+    /// - `entities` The entities to move
+    /// - `to` The `World` to move them into
+    ///
+    /// # Returns
+    /// `Result<EntityMap, EcsError>` - The new handle of each moved entity, or an error if any of
+    /// them is not alive, or has a dynamic component that `to` registered with a different layout
+    /// or storage, in which case nothing is moved, or if a moved component refers to an entity
+    /// that was not moved, which is only reported once every entity has been moved
+    pub fn transfer_entities(
+        &mut self,
+        entities: &[Entity],
+        to: &mut World,
+    ) -> Result<EntityMap, EcsError> {
+        if let Some(entity) = entities.iter().find(|entity| !self.is_alive(entity)) {
+            return Err(EcsError::EntityNotFound(entity.clone()));
+        }
+        for entity in entities {
+            for id in self.component_ids_of(entity) {
+                to.component_registry()
+                    .check_import(self.component_registry(), id)?;
+            }
+        }
+
+        let mut links = vec![];
+        for parent in entities {
+            if let Some(children) = self.get_component::<Children>(parent) {
+                links.extend(
+                    children
+                        .iter()
+                        .filter(|child| entities.contains(child))
+                        .map(|child| (parent.clone(), child.clone())),
+                );
+            }
+        }
+
+        let mut map = EntityMap::default();
+        for entity in entities {
+            if map.get(entity).is_some() {
+                continue;
+            }
+            self.detach_hierarchy(entity);
+            let despawned = self.trigger_despawn_hooks(entity);
+            let Some(components) = self.take_entity(entity) else {
+                continue;
+            };
+            // A remove hook despawned the entity, so its components are dropped rather than moved.
+            if despawned {
+                continue;
+            }
+            let moved = to.spawn_taken(self.component_registry(), components);
+            map.insert(entity.clone(), moved);
+        }
+
+        for (parent, child) in links {
+            if let (Some(parent), Some(child)) = (map.get(&parent), map.get(&child))
+                && to.is_alive(parent)
+                && to.is_alive(child)
+            {
+                to.set_parent(child, parent);
+            }
+        }
+
+        let mappers: Vec<_> = to
+            .type_registry()
+            .iter()
+            .filter_map(|registration| registration.entity_mapper())
+            .collect();
+        for mapper in mappers {
+            for (_, entity) in map.iter() {
+                mapper(to, entity, &map)?;
+            }
+        }
+
+        Ok(map)
+    }
+}
@@ -0,0 +1,368 @@
+use std::{any::type_name, collections::HashMap};
+
+use crate::{
+    ComponentId, EcsError, Entity, EntityMap, MapEntities, Reflect, ReflectValue, Resource, World,
+    from_reflect_value, to_reflect_value,
+};
+
+/// Reads a field of the component of an entity, or `None` if the entity does not have it.
+type FieldGetter = fn(&World, &Entity, &str) -> Option<Result<ReflectValue, EcsError>>;
+
+/// Writes a field of the component of an entity, or `None` if the entity does not have it.
+type FieldSetter = fn(&mut World, &Entity, &str, ReflectValue) -> Option<Result<(), EcsError>>;
+
+/// Points the entity references of one component type at the entities spawned by a scene.
+pub(crate) type EntityMapper = fn(&mut World, &Entity, &EntityMap) -> Result<(), EcsError>;
+
+/// How to read and write one registered component type without knowing it statically.
+#[derive(Clone, Copy)]
+pub struct TypeRegistration {
+    name: &'static str,
+    type_name: &'static str,
+    component_id: ComponentId,
+    field_names: &'static [&'static str],
+    get: fn(&World, &Entity) -> Option<Result<ReflectValue, EcsError>>,
+    insert: fn(&mut World, &Entity, ReflectValue) -> Result<(), EcsError>,
+    get_field: FieldGetter,
+    set_field: FieldSetter,
+    map_entities: Option<EntityMapper>,
+}
+
+impl TypeRegistration {
+    /// The name the type is looked up by, e.g. `Position`.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The full path of the type, e.g. `my_game::components::Position`.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    pub fn component_id(&self) -> ComponentId {
+        self.component_id
+    }
+
+    /// The name of every field of the type, see `Reflect::field_names`.
+    pub fn field_names(&self) -> &'static [&'static str] {
+        self.field_names
+    }
+
+    /// Reads the component from `entity`, or `None` if the entity does not have it.
+    pub(crate) fn reflect(
+        &self,
+        world: &World,
+        entity: &Entity,
+    ) -> Option<Result<ReflectValue, EcsError>> {
+        (self.get)(world, entity)
+    }
+
+    pub(crate) fn entity_mapper(&self) -> Option<EntityMapper> {
+        self.map_entities
+    }
+}
+
+/// Maps component names to their `ComponentId` and to the accessors of their `Reflect`
+/// implementation, so editors, save files and debuggers can work with components by name.
+///
+/// Every `World` starts with one as a resource. Types are added with `World::register_type`.
+#[derive(Default)]
+pub struct TypeRegistry {
+    registrations: Vec<TypeRegistration>,
+    by_name: HashMap<&'static str, usize>,
+    by_id: HashMap<ComponentId, usize>,
+}
+impl Resource for TypeRegistry {}
+
+impl TypeRegistry {
+    pub fn get(&self, name: &str) -> Option<&TypeRegistration> {
+        self.by_name
+            .get(name)
+            .map(|index| &self.registrations[*index])
+    }
+
+    pub fn get_by_id(&self, id: ComponentId) -> Option<&TypeRegistration> {
+        self.by_id.get(&id).map(|index| &self.registrations[*index])
+    }
+
+    /// Every registered type, in the order they were registered.
+    pub fn iter(&self) -> impl Iterator<Item = &TypeRegistration> {
+        self.registrations.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.registrations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.registrations.is_empty()
+    }
+
+    /// Adds `registration`, unless the same type is already registered under the same name.
+    ///
+    /// # Returns
+    /// `Result<(), EcsError>` - An error if the name is taken by another type, or the type is
+    /// already registered under another name
+    fn add(&mut self, registration: TypeRegistration) -> Result<(), EcsError> {
+        if let Some(index) = self.by_name.get(registration.name) {
+            let existing = &mut self.registrations[*index];
+            if existing.component_id == registration.component_id {
+                existing.map_entities = existing.map_entities.or(registration.map_entities);
+                return Ok(());
+            }
+            return Err(EcsError::TypeNameTaken {
+                name: registration.name,
+                type_name: existing.type_name,
+            });
+        }
+        if let Some(existing) = self.get_by_id(registration.component_id) {
+            return Err(EcsError::TypeAlreadyRegistered {
+                type_name: registration.type_name,
+                name: existing.name,
+            });
+        }
+
+        let index = self.registrations.len();
+        self.by_name.insert(registration.name, index);
+        self.by_id.insert(registration.component_id, index);
+        self.registrations.push(registration);
+        Ok(())
+    }
+}
+
+impl World {
+    /// Registers `T` in the `TypeRegistry` under its type name without the module path, e.g.
+    /// `Position`. Registering the same type again does nothing.
+    ///
+    /// # Panics
+    /// If a different type is already registered under the same name, or `T` is already
+    /// registered under another name. See `World::try_register_type_named` for a non-panicking
+    /// version.
+    pub fn register_type<T: Reflect>(&mut self) {
+        self.register_type_named::<T>(short_name(type_name::<T>()));
+    }
+
+    /// Registers `T` in the `TypeRegistry` under `name`.
+    ///
+    /// # Panics
+    /// If a different type is already registered under `name`, or `T` is already registered
+    /// under another name. See `World::try_register_type_named` for a non-panicking version.
+    pub fn register_type_named<T: Reflect>(&mut self, name: &'static str) {
+        if let Err(e) = self.try_register_type_named::<T>(name) {
+            panic!("{e}");
+        }
+    }
+
+    /// Registers `T` in the `TypeRegistry` under `name`. Registering the same type under the
+    /// same name again does nothing.
+    ///
+    /// # Returns
+    /// `Result<(), EcsError>` - An error if a different type is already registered under `name`,
+    /// or `T` is already registered under another name
+    pub fn try_register_type_named<T: Reflect>(
+        &mut self,
+        name: &'static str,
+    ) -> Result<(), EcsError> {
+        let registration = Self::registration_for::<T>(self.component_id::<T>(), name);
+        self.type_registry_mut().add(registration)
+    }
+
+    /// Registers `T` like `World::register_type`, and remaps the entities it references when a
+    /// `Scene` is loaded.
+    ///
+    /// # Panics
+    /// If a different type is already registered under the same name, or `T` is already
+    /// registered under another name
+    pub fn register_type_with_entities<T>(&mut self)
+    where
+        T: Reflect + MapEntities,
+    {
+        let mut registration =
+            Self::registration_for::<T>(self.component_id::<T>(), short_name(type_name::<T>()));
+        registration.map_entities =
+            Some(
+                |world, entity, entities| match world.get_component_mut::<T>(entity) {
+                    Some(component) => component.map_entities(entities),
+                    None => Ok(()),
+                },
+            );
+        if let Err(e) = self.type_registry_mut().add(registration) {
+            panic!("{e}");
+        }
+    }
+
+    fn registration_for<T: Reflect>(
+        component_id: ComponentId,
+        name: &'static str,
+    ) -> TypeRegistration {
+        TypeRegistration {
+            name,
+            type_name: type_name::<T>(),
+            component_id,
+            field_names: T::field_names(),
+            get: |world, entity| world.get_component::<T>(entity).map(to_reflect_value),
+            insert: |world, entity, value| {
+                let component = from_reflect_value::<T>(value)?;
+                if !world.is_alive(entity) {
+                    return Err(EcsError::EntityNotFound(entity.clone()));
+                }
+                world.insert_component(entity, component);
+                Ok(())
+            },
+            get_field: |world, entity, field| {
+                world
+                    .get_component::<T>(entity)
+                    .map(|component| component.field(field))
+            },
+            set_field: |world, entity, field, value| {
+                let change_tick = world.change_tick();
+                let (component, ticks) = world.get_component_and_ticks_mut::<T>(entity)?;
+                // Only a field that was actually written marks the component as changed.
+                let result = component.set_field(field, value);
+                if result.is_ok() {
+                    ticks.changed = change_tick;
+                }
+                Some(result)
+            },
+            map_entities: None,
+        }
+    }
+
+    pub fn type_registry(&self) -> &TypeRegistry {
+        self.resource::<TypeRegistry>()
+            .expect("TypeRegistry resource was removed from the World")
+    }
+
+    fn type_registry_mut(&mut self) -> &mut TypeRegistry {
+        self.resource_mut::<TypeRegistry>()
+            .expect("TypeRegistry resource was removed from the World")
+    }
+
+    /// Reads the registered component called `component` from `entity`.
+    ///
+    /// # Returns
+    /// `Result<ReflectValue, EcsError>` - The component, or an error if the entity is not alive,
+    /// the name is not registered, or the entity does not have that component
+    pub fn reflect_component(
+        &self,
+        entity: &Entity,
+        component: &str,
+    ) -> Result<ReflectValue, EcsError> {
+        let registration = self.registration(component)?;
+        if !self.is_alive(entity) {
+            return Err(EcsError::EntityNotFound(entity.clone()));
+        }
+        (registration.get)(self, entity)
+            .unwrap_or_else(|| Err(missing_component(entity, registration)))
+    }
+
+    /// Reads every registered component of `entity`, in registration order.
+    ///
+    /// # Returns
+    /// `Result<Vec<(&'static str, ReflectValue)>, EcsError>` - The name and value of each
+    /// component, or an error if the entity is not alive
+    pub fn reflect_entity(
+        &self,
+        entity: &Entity,
+    ) -> Result<Vec<(&'static str, ReflectValue)>, EcsError> {
+        if !self.is_alive(entity) {
+            return Err(EcsError::EntityNotFound(entity.clone()));
+        }
+        let mut components = vec![];
+        for registration in self.type_registry().iter() {
+            if let Some(value) = (registration.get)(self, entity) {
+                components.push((registration.name, value?));
+            }
+        }
+        Ok(components)
+    }
+
+    /// Reads the field `field` of the registered component called `component`, see
+    /// `Reflect::field`. The fields of tuple structs are named by their index.
+    pub fn get_field(
+        &self,
+        entity: &Entity,
+        component: &str,
+        field: &str,
+    ) -> Result<ReflectValue, EcsError> {
+        let registration = self.field_registration(entity, component, field)?;
+        (registration.get_field)(self, entity, field)
+            .unwrap_or_else(|| Err(missing_component(entity, registration)))
+    }
+
+    /// Overwrites the field `field` of the registered component called `component`, marking the
+    /// component as changed, see `Reflect::set_field`. The component is left untouched if
+    /// `value` does not fit the field.
+    ///
+    /// # Returns
+    /// `Result<(), EcsError>` - An error if the component or field can't be found, or `value`
+    /// does not fit the field
+    pub fn set_field(
+        &mut self,
+        entity: &Entity,
+        component: &str,
+        field: &str,
+        value: impl Into<ReflectValue>,
+    ) -> Result<(), EcsError> {
+        let registration = *self.field_registration(entity, component, field)?;
+        (registration.set_field)(self, entity, field, value.into())
+            .unwrap_or_else(|| Err(missing_component(entity, &registration)))
+    }
+
+    /// Adds the registered component called `component` to `entity`, deserialized from `value`,
+    /// replacing the existing one if the entity already has it.
+    ///
+    /// # Returns
+    /// `Result<(), EcsError>` - An error if the entity is not alive, the name is not registered,
+    /// or `value` does not deserialize into the component
+    pub fn insert_by_name(
+        &mut self,
+        entity: &Entity,
+        component: &str,
+        value: ReflectValue,
+    ) -> Result<(), EcsError> {
+        let registration = *self.registration(component)?;
+        (registration.insert)(self, entity, value)
+    }
+
+    fn registration(&self, name: &str) -> Result<&TypeRegistration, EcsError> {
+        self.type_registry()
+            .get(name)
+            .ok_or_else(|| EcsError::UnknownTypeName(name.to_string()))
+    }
+
+    /// The registration of `component`, checking that `entity` is alive and the component has
+    /// a field called `field`.
+    fn field_registration(
+        &self,
+        entity: &Entity,
+        component: &str,
+        field: &str,
+    ) -> Result<&TypeRegistration, EcsError> {
+        let registration = self.registration(component)?;
+        if !self.is_alive(entity) {
+            return Err(EcsError::EntityNotFound(entity.clone()));
+        }
+        if !registration.field_names.contains(&field) {
+            return Err(EcsError::UnknownField {
+                component: component.to_string(),
+                field: field.to_string(),
+            });
+        }
+        Ok(registration)
+    }
+}
+
+fn missing_component(entity: &Entity, registration: &TypeRegistration) -> EcsError {
+    EcsError::MissingComponent {
+        entity: entity.clone(),
+        component: registration.name,
+    }
+}
+
+/// The name of a type without its module path, keeping any generic parameters.
+fn short_name(type_name: &'static str) -> &'static str {
+    let end = type_name.find('<').unwrap_or(type_name.len());
+    let start = type_name[..end].rfind("::").map_or(0, |index| index + 2);
+    &type_name[start..]
+}
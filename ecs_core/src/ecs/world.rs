@@ -1,34 +1,95 @@
-use std::collections::HashMap;
+use std::{any::TypeId, collections::HashMap, panic::Location, sync::Arc};
 
 use crate::{
-    Component, ComponentId, ComponentList, ComponentListOps, SystemParam,
-    ecs::{ComponentRegistry, Entity, EntityRegistry},
+    Access, Archetype, ArchetypeId, Bundle, Children, CommandQueue, Component, ComponentId,
+    ComponentList, ComponentTicks, EcsError, EntityLocation, Event, Events, Parent, Resource,
+    ResourceId, Resources, StorageType, SystemParam, Tick, TypeRegistry, UnsafeWorldCell,
+    WorldSnapshot,
+    ecs::{
+        ComponentColumn, ComponentHook, ComponentHooks, ComponentListOps, ComponentRegistry,
+        Entity, EntityRegistry, EventCursors, HookKind, SparseSet, TakenComponent,
+    },
 };
 
-type EntityIndex = usize;
-type ArchetypeIndex = usize;
+/// The archetype of entities without any components. Always the first one created.
+const EMPTY_ARCHETYPE: ArchetypeId = 0;
+
+/// Archetypes matched by a query, the tick the query last ran at, and the cursors of its
+/// `EventReader`s.
+///
+/// `seen` is the number of entries in `World::archetypes` that have already been tested,
+/// so newly created archetypes are checked once instead of on every query.
+/// `entities` is the buffer the matching entities are collected into, kept between runs so
+/// a query doesn't allocate every time it runs.
+///
+/// `World::system` keeps one per query type and call site. Scheduled systems own their own,
+/// so change filters are tracked per system. A state must only be used with a single query type.
+#[derive(Default)]
+pub struct QueryState {
+    archetypes: Vec<ArchetypeId>,
+    seen: usize,
+    last_run: Tick,
+    entities: Vec<Entity>,
+    event_cursors: EventCursors,
+}
+
+impl QueryState {
+    /// Keeps the buffer returned by `World::query_entities` for the next run of the query.
+    pub(crate) fn reuse_entities(&mut self, mut entities: Vec<Entity>) {
+        entities.clear();
+        self.entities = entities;
+    }
+}
 
 pub struct World {
     entity_registry: EntityRegistry,
-    entities: HashMap<Entity, HashMap<ComponentId, EntityIndex>>,
-    entity_lookup: HashMap<(ComponentId, EntityIndex), Entity>,
+    /// Indexed by entity id. Only valid for live entities, see `World::location`.
+    locations: Vec<Option<EntityLocation>>,
     component_registry: ComponentRegistry,
-    components: Vec<Box<dyn ComponentListOps>>,
-    archetype_lookup: HashMap<Entity, ArchetypeIndex>,
-    archetypes: HashMap<Vec<ComponentId>, Vec<Entity>>,
+    archetypes: Vec<Archetype>,
+    archetype_index: HashMap<Vec<ComponentId>, ArchetypeId>,
+    /// Indexed by `ComponentId`, only set for `StorageType::SparseSet` components.
+    sparse_sets: Vec<Option<SparseSet>>,
+    /// The state of `World::system` and `World::entity_system`, per query type and call site.
+    query_states: HashMap<(TypeId, &'static Location<'static>), QueryState>,
+    change_tick: Tick,
+    resources: Resources,
+    event_updaters: HashMap<ResourceId, fn(&mut World)>,
+    hooks: HashMap<ComponentId, ComponentHooks>,
+    /// Entities whose remove hooks are running before they leave the `World`, with the components
+    /// whose hooks have already run, so no hook runs twice for the same despawn.
+    despawning: Vec<(Entity, Vec<ComponentId>)>,
+    /// Components whose remove hooks are running before they are removed, so a hook removing
+    /// the component again doesn't run them twice.
+    removing: Vec<(Entity, ComponentId)>,
+    /// Entities despawned by a remove hook, which are despawned once the component has been
+    /// removed.
+    deferred_despawns: Vec<Entity>,
 }
 
 impl World {
     pub fn new() -> Self {
-        Self {
+        let mut world = Self {
             entity_registry: EntityRegistry::new(),
-            entities: HashMap::new(),
-            entity_lookup: HashMap::new(),
+            locations: Vec::new(),
             component_registry: ComponentRegistry::new(),
-            components: Vec::new(),
-            archetype_lookup: HashMap::new(),
-            archetypes: HashMap::new(),
-        }
+            archetypes: vec![Archetype::new(EMPTY_ARCHETYPE, vec![], vec![])],
+            archetype_index: HashMap::from([(vec![], EMPTY_ARCHETYPE)]),
+            sparse_sets: Vec::new(),
+            query_states: HashMap::new(),
+            change_tick: 1,
+            resources: Resources::default(),
+            event_updaters: HashMap::new(),
+            hooks: HashMap::new(),
+            despawning: Vec::new(),
+            removing: Vec::new(),
+            deferred_despawns: Vec::new(),
+        };
+        world.insert_resource(CommandQueue::default());
+        world.insert_resource(TypeRegistry::default());
+        world.register_snapshot::<Parent>();
+        world.register_snapshot::<Children>();
+        world
     }
 
     /// Retrieves the `ComponentId` from the `ComponentRegistry` for a given Type that implements `Component`
@@ -42,179 +103,1199 @@ impl World {
         self.component_registry.id::<T>()
     }
 
+    /// Retrieves the `ResourceId` for a given Type that implements `Resource`, registering it if needed.
+    pub fn resource_id<R: Resource>(&mut self) -> ResourceId {
+        self.resources.id::<R>()
+    }
+
+    /// Inserts a global `Resource`, replacing any existing value of the same type.
+    ///
+    /// # Returns
+    /// `Option<R>` - The resource that was replaced, if any
+    pub fn insert_resource<R: Resource>(&mut self, resource: R) -> Option<R> {
+        self.resources.insert(resource)
+    }
+
+    pub fn remove_resource<R: Resource>(&mut self) -> Option<R> {
+        self.resources.remove::<R>()
+    }
+
+    pub fn contains_resource<R: Resource>(&self) -> bool {
+        self.resources.contains::<R>()
+    }
+
+    pub fn resource<R: Resource>(&self) -> Option<&R> {
+        self.resources.get::<R>()
+    }
+
+    pub fn resource_mut<R: Resource>(&mut self) -> Option<&mut R> {
+        self.resources.get_mut::<R>()
+    }
+
+    /// Adds an `Events<E>` resource, which `World::update_events` will update.
+    pub fn add_event<E: Event>(&mut self) {
+        let id = self.resource_id::<Events<E>>();
+        if !self.contains_resource::<Events<E>>() {
+            self.insert_resource(Events::<E>::default());
+        }
+        self.event_updaters.insert(id, |world| {
+            if let Some(events) = world.resource_mut::<Events<E>>() {
+                events.update();
+            }
+        });
+    }
+
+    /// Sends `event` from outside of a system. It is read by every system that runs afterwards.
+    ///
+    /// # Returns
+    /// `bool` - Whether the event was sent, which requires `World::add_event` to have been called
+    pub fn send_event<E: Event>(&mut self, event: E) -> bool {
+        // Readers skip events stamped with their own run, so stamp it with the last one.
+        let tick = self.change_tick - 1;
+        match self.resource_mut::<Events<E>>() {
+            Some(events) => {
+                events.send(event, tick);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Drops the events sent before the previous call, for every event type added with
+    /// `World::add_event`. Meant to be called once per frame.
+    pub fn update_events(&mut self) {
+        let updaters: Vec<fn(&mut World)> = self.event_updaters.values().copied().collect();
+        for update in updaters {
+            update(self);
+        }
+    }
+
+    /// Registers `hook` to run after a `T` is added to an entity that did not have one.
+    pub fn on_add<T: Component>(
+        &mut self,
+        hook: impl Fn(&mut World, &Entity) + Send + Sync + 'static,
+    ) {
+        self.add_hook::<T>(HookKind::Add, Arc::new(hook));
+    }
+
+    /// Registers `hook` to run after a `T` is added to an entity or replaced.
+    pub fn on_insert<T: Component>(
+        &mut self,
+        hook: impl Fn(&mut World, &Entity) + Send + Sync + 'static,
+    ) {
+        self.add_hook::<T>(HookKind::Insert, Arc::new(hook));
+    }
+
+    /// Registers `hook` to run before a `T` is removed from an entity, including when the entity
+    /// is despawned. The component can still be read from the hook.
+    ///
+    /// Removing the same component from the hook does nothing, and despawning the entity from it
+    /// is deferred until the component has been removed.
+    pub fn on_remove<T: Component>(
+        &mut self,
+        hook: impl Fn(&mut World, &Entity) + Send + Sync + 'static,
+    ) {
+        self.add_hook::<T>(HookKind::Remove, Arc::new(hook));
+    }
+
+    /// Registers `hook` to run at the `kind` point of the lifecycle of `T` components. Hooks
+    /// run in the order they were registered.
+    pub fn add_hook<T: Component>(&mut self, kind: HookKind, hook: ComponentHook) {
+        let id = self.component_id::<T>();
+        self.hooks.entry(id).or_default().push(kind, hook);
+    }
+
+    /// Runs the `kind` hooks of the component `id` for `entity`.
+    pub(crate) fn trigger_hooks(&mut self, kind: HookKind, id: ComponentId, entity: &Entity) {
+        let Some(hooks) = self.hooks.get(&id) else {
+            return;
+        };
+        // Hooks get the whole world, so they can't be borrowed from it while running.
+        let hooks: Vec<ComponentHook> = hooks.get(kind).to_vec();
+        for hook in hooks {
+            hook(self, entity);
+        }
+    }
+
+    /// Runs the remove hooks of the component `id` for `entity`, unless they already ran while
+    /// despawning it.
+    fn trigger_remove_hooks(&mut self, id: ComponentId, entity: &Entity) {
+        if let Some((_, triggered)) = self
+            .despawning
+            .iter_mut()
+            .find(|(despawning, _)| despawning == entity)
+        {
+            if triggered.contains(&id) {
+                return;
+            }
+            triggered.push(id);
+        }
+
+        let removing = (entity.clone(), id);
+        self.removing.push(removing.clone());
+        self.trigger_hooks(HookKind::Remove, id, entity);
+        self.removing.retain(|other| *other != removing);
+    }
+
+    pub(crate) fn resources(&self) -> &Resources {
+        &self.resources
+    }
+
+    pub(crate) fn component_registry(&self) -> &ComponentRegistry {
+        &self.component_registry
+    }
+
+    pub(crate) fn component_registry_mut(&mut self) -> &mut ComponentRegistry {
+        &mut self.component_registry
+    }
+
+    /// Every archetype, indexed by `ArchetypeId`.
+    pub fn archetypes(&self) -> &[Archetype] {
+        &self.archetypes
+    }
+
+    /// Where the components of `entity` are stored, or `None` if it is not alive.
+    pub fn location(&self, entity: &Entity) -> Option<EntityLocation> {
+        if !self.entity_registry.is_alive(entity) {
+            return None;
+        }
+        self.locations.get(entity.id() as usize).copied().flatten()
+    }
+
+    /// The column holding the `id` component of `entity`, and the entity's row in it.
+    pub(crate) fn component_column(
+        &self,
+        entity: &Entity,
+        id: ComponentId,
+    ) -> Option<(&ComponentColumn, usize)> {
+        let location = self.location(entity)?;
+        match self.component_registry.storage_type(id)? {
+            StorageType::Table => {
+                let column = self.archetypes[location.archetype].column(id)?;
+                Some((column, location.row))
+            }
+            StorageType::SparseSet => {
+                let set = self.sparse_set(id)?;
+                Some((set.column(), set.row(entity)?))
+            }
+        }
+    }
+
+    pub(crate) fn component_column_mut(
+        &mut self,
+        entity: &Entity,
+        id: ComponentId,
+    ) -> Option<(&mut ComponentColumn, usize)> {
+        let location = self.location(entity)?;
+        match self.component_registry.storage_type(id)? {
+            StorageType::Table => {
+                let column = self.archetypes[location.archetype].column_mut(id)?;
+                Some((column, location.row))
+            }
+            StorageType::SparseSet => {
+                let set = self.sparse_sets.get_mut(id as usize)?.as_mut()?;
+                let row = set.row(entity)?;
+                Some((set.column_mut(), row))
+            }
+        }
+    }
+
+    pub(crate) fn sparse_set(&self, id: ComponentId) -> Option<&SparseSet> {
+        self.sparse_sets.get(id as usize)?.as_ref()
+    }
+
+    /// Whether `entity` has a component with the given id, regardless of how it is stored.
+    pub fn has_component_id(&self, entity: &Entity, id: ComponentId) -> bool {
+        self.component_column(entity, id).is_some()
+    }
+
+    pub fn get_component<T: Component>(&self, entity: &Entity) -> Option<&T> {
+        let id = self.component_registry.get::<T>()?;
+        let (column, row) = self.component_column(entity, id)?;
+        column
+            .get()
+            .as_any()
+            .downcast_ref::<ComponentList<T>>()?
+            .components
+            .get(row)
+    }
+
+    /// Mutably borrows a component of `entity`, marking it as changed at the current tick.
     pub fn get_component_mut<T: Component>(&mut self, entity: &Entity) -> Option<&mut T> {
-        let id = self.component_registry.id::<T>();
-        let index = *self.entities.get(entity)?.get(&id)?;
-        self.components[id as usize]
+        let change_tick = self.change_tick;
+        let (component, ticks) = self.get_component_and_ticks_mut::<T>(entity)?;
+        ticks.changed = change_tick;
+        Some(component)
+    }
+
+    /// Borrows the `T` component of `entity` along with its ticks, without marking it as
+    /// changed.
+    pub(crate) fn get_component_and_ticks_mut<T: Component>(
+        &mut self,
+        entity: &Entity,
+    ) -> Option<(&mut T, &mut ComponentTicks)> {
+        let id = self.component_registry.get::<T>()?;
+        let (column, row) = self.component_column_mut(entity, id)?;
+        let list = column
+            .get_mut()
             .as_any_mut()
-            .downcast_mut::<ComponentList<T>>()?
-            .components
-            .get_mut(index)
+            .downcast_mut::<ComponentList<T>>()?;
+        Some((list.components.get_mut(row)?, list.ticks.get_mut(row)?))
     }
 
-    pub fn system<P, F>(&mut self, mut f: F)
+    /// The current change tick. Advances every time a system runs.
+    pub fn change_tick(&self) -> Tick {
+        self.change_tick
+    }
+
+    /// Advances the change tick by `count`, so runs scheduled ahead of time can be stamped with
+    /// the ticks they would have had if run one after another.
+    ///
+    /// # Returns
+    /// `Tick` - The first of the reserved ticks
+    pub(crate) fn reserve_ticks(&mut self, count: usize) -> Tick {
+        let first = self.change_tick;
+        self.change_tick += count as Tick;
+        first
+    }
+
+    /// Runs `f` for every entity that matches the query `P`. Queries that only use resources
+    /// also run once per entity, see `World::resource_system` to run them once.
+    ///
+    /// Change filters such as `Changed<T>` compare against the last time this call site ran, so
+    /// a call inside a helper function shares that state with every caller of the helper.
+    ///
+    /// # Panics
+    /// If `P` borrows the same component more than once, see `World::query_access`.
+    #[track_caller]
+    pub fn system<P, F>(&mut self, f: F)
+    where
+        for<'w> P: SystemParam<'w> + 'static,
+        for<'w> F: FnMut(Entity, <P as SystemParam<'w>>::Item),
+    {
+        let key = (TypeId::of::<P>(), Location::caller());
+        let mut state = self.query_states.remove(&key).unwrap_or_default();
+        self.system_with_state::<P, F>(&mut state, f);
+        self.query_states.insert(key, state);
+    }
+
+    /// Runs `f` for every entity that matches the query `P`, using `state` to cache matching
+    /// archetypes and track when the query last ran.
+    ///
+    /// # Returns
+    /// `usize` - The number of entities `f` ran for
+    ///
+    /// # Panics
+    /// If `P` borrows the same component more than once, see `World::query_access`.
+    pub fn system_with_state<P, F>(&mut self, state: &mut QueryState, f: F) -> usize
     where
         for<'w> P: SystemParam<'w>,
         for<'w> F: FnMut(Entity, <P as SystemParam<'w>>::Item),
     {
-        let entities: Vec<Entity> = self.entities.keys().cloned().collect();
+        let access = self.query_access::<P>();
+        let this_run = self.change_tick;
+        self.change_tick += 1;
+        let world = UnsafeWorldCell::new(self, this_run, this_run);
+
+        // SAFETY: `query_access` rejected any aliasing borrows, and nothing else can borrow the
+        // world while `self` is mutably borrowed.
+        let matched = unsafe { Self::system_unchecked::<P, F>(world, state, &access, f) };
+        self.apply_commands();
+        matched
+    }
 
-        for entity in entities {
-            if let Some(params) = P::fetch(self, &entity) {
-                f(entity, params);
+    /// Applies every operation recorded by `Commands` since the last sync point.
+    pub fn apply_commands(&mut self) {
+        // Applying a command can record new ones, e.g. if it runs a system.
+        while let Some(queue) = self.resources.get_mut::<CommandQueue>()
+            && !queue.is_empty()
+        {
+            let mut queue = std::mem::take(queue);
+            queue.apply(self);
+        }
+    }
+
+    /// Runs `f` for every entity that matches the query `P` through a shared `UnsafeWorldCell`,
+    /// so queries with disjoint access can run at the same time. The run is stamped with the
+    /// cell's `this_run` tick.
+    ///
+    /// # Returns
+    /// `usize` - The number of entities `f` ran for
+    ///
+    /// # Safety
+    /// `access` must be the `Access` of `P` returned by `World::query_access`, and no other borrow
+    /// of the components or resources it uses may be live until this returns. The components and
+    /// archetypes of the world must not change while it runs.
+    pub(crate) unsafe fn system_unchecked<P, F>(
+        world: UnsafeWorldCell<'_>,
+        state: &mut QueryState,
+        access: &Access,
+        mut f: F,
+    ) -> usize
+    where
+        for<'w> P: SystemParam<'w>,
+        for<'w> F: FnMut(Entity, <P as SystemParam<'w>>::Item),
+    {
+        let entities = unsafe { world.world() }.query_entities(state, access);
+        let last_run = std::mem::replace(&mut state.last_run, world.this_run());
+        let world = world
+            .with_last_run(last_run)
+            .with_event_cursors(&state.event_cursors);
+
+        let mut matched = 0;
+        for entity in &entities {
+            // SAFETY: The caller guarantees the access of `P` is exclusive, and `f` cannot keep
+            // the items of one entity alive while the next one is fetched.
+            if unsafe { world.world() }.matches_sparse(access, entity)
+                && unsafe { P::matches(world, entity) }
+                && let Some(params) = unsafe { P::fetch(world, entity) }
+            {
+                f(entity.clone(), params);
+                matched += 1;
             }
         }
+
+        state.reuse_entities(entities);
+        matched
     }
 
-    pub fn entity_system<P, F>(&mut self, entity: &Entity, mut f: F)
+    /// Runs `f` once for the query `P`, which may only use resources, such as `Res`, `ResMut`,
+    /// `Commands` or events.
+    ///
+    /// Unlike `World::system`, which runs `f` for every matching entity, `f` runs exactly once
+    /// regardless of how many entities exist. Like `World::system`, its state is kept per call
+    /// site.
+    ///
+    /// # Returns
+    /// `bool` - Whether `f` ran, which requires every resource `P` borrows to exist
+    ///
+    /// # Panics
+    /// If `P` uses a component, or borrows the same resource more than once
+    #[track_caller]
+    pub fn resource_system<P, F>(&mut self, f: F) -> bool
+    where
+        for<'w> P: SystemParam<'w> + 'static,
+        for<'w> F: FnOnce(<P as SystemParam<'w>>::Item),
+    {
+        let key = (TypeId::of::<P>(), Location::caller());
+        let mut state = self.query_states.remove(&key).unwrap_or_default();
+        let ran = self.resource_system_with_state::<P, F>(&mut state, f);
+        self.query_states.insert(key, state);
+        ran
+    }
+
+    /// Runs `f` once for the query `P`, which may only use resources, using `state` to track
+    /// when it last ran.
+    ///
+    /// # Returns
+    /// `bool` - Whether `f` ran, which requires every resource `P` borrows to exist
+    ///
+    /// # Panics
+    /// If `P` uses a component, or borrows the same resource more than once
+    pub fn resource_system_with_state<P, F>(&mut self, state: &mut QueryState, f: F) -> bool
+    where
+        for<'w> P: SystemParam<'w>,
+        for<'w> F: FnOnce(<P as SystemParam<'w>>::Item),
+    {
+        self.resource_access::<P>();
+        let this_run = self.change_tick;
+        self.change_tick += 1;
+        let world = UnsafeWorldCell::new(self, this_run, this_run);
+
+        // SAFETY: `resource_access` rejected any aliasing borrows, and nothing else can borrow
+        // the world while `self` is mutably borrowed.
+        let ran = unsafe { Self::resource_system_unchecked::<P, F>(world, state, f) };
+        self.apply_commands();
+        ran
+    }
+
+    /// Runs `f` once for the query `P` through a shared `UnsafeWorldCell`, stamped with the
+    /// cell's `this_run` tick.
+    ///
+    /// # Returns
+    /// `bool` - Whether `f` ran
+    ///
+    /// # Safety
+    /// `P` must have passed `World::resource_access`, and no other borrow of the resources it
+    /// uses may be live until this returns.
+    pub(crate) unsafe fn resource_system_unchecked<P, F>(
+        world: UnsafeWorldCell<'_>,
+        state: &mut QueryState,
+        f: F,
+    ) -> bool
     where
         for<'w> P: SystemParam<'w>,
+        for<'w> F: FnOnce(<P as SystemParam<'w>>::Item),
+    {
+        let last_run = std::mem::replace(&mut state.last_run, world.this_run());
+        let world = world
+            .with_last_run(last_run)
+            .with_event_cursors(&state.event_cursors);
+
+        // SAFETY: The caller guarantees the access of `P` is exclusive.
+        match unsafe { P::fetch_once(world) } {
+            Some(params) => {
+                f(params);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Runs `f` for a single entity if it matches the query `P`.
+    ///
+    /// Like `World::system`, change filters compare against the last time this call site ran.
+    ///
+    /// # Panics
+    /// If `P` borrows the same component more than once, see `World::query_access`.
+    #[track_caller]
+    pub fn entity_system<P, F>(&mut self, entity: &Entity, mut f: F)
+    where
+        for<'w> P: SystemParam<'w> + 'static,
         for<'w> F: FnMut(Entity, <P as SystemParam<'w>>::Item),
     {
-        if let Some(params) = P::fetch(self, &entity) {
+        let access = self.query_access::<P>();
+        let Some(location) = self.location(entity) else {
+            return;
+        };
+        if !access.matches_archetype(self.archetypes[location.archetype].component_ids())
+            || !self.matches_sparse(&access, entity)
+        {
+            return;
+        }
+
+        let key = (TypeId::of::<P>(), Location::caller());
+        let mut state = self.query_states.remove(&key).unwrap_or_default();
+        let (last_run, this_run) = self.begin_run(&mut state);
+        let world =
+            UnsafeWorldCell::new(self, last_run, this_run).with_event_cursors(&state.event_cursors);
+
+        // SAFETY: `query_access` rejected any aliasing borrows.
+        if unsafe { P::matches(world, entity) }
+            && let Some(params) = unsafe { P::fetch(world, entity) }
+        {
             f(entity.clone(), params);
         }
+        self.query_states.insert(key, state);
+        self.apply_commands();
+    }
+
+    /// Advances the change tick for a run of the query tracked by `state`.
+    ///
+    /// # Returns
+    /// `(Tick, Tick)` - The tick the query last ran at, and the tick of this run
+    fn begin_run(&mut self, state: &mut QueryState) -> (Tick, Tick) {
+        let this_run = self.change_tick;
+        self.change_tick += 1;
+
+        let last_run = std::mem::replace(&mut state.last_run, this_run);
+        (last_run, this_run)
+    }
+
+    /// Collects the `Access` of the query `P`, registering any components it uses.
+    ///
+    /// # Panics
+    /// If `P` asks for the same component or resource more than once, since fetching it would create
+    /// aliasing mutable borrows.
+    pub fn query_access<P>(&mut self) -> Access
+    where
+        for<'w> P: SystemParam<'w>,
+    {
+        let mut access = Access::default();
+        P::access(self, &mut access);
+
+        if let Some(id) = access.conflicts().first() {
+            panic!(
+                "Query requests conflicting access to component `{}`",
+                self.component_registry.name(*id).unwrap_or("unknown")
+            );
+        }
+
+        if let Some(id) = access.resource_conflicts().first() {
+            panic!(
+                "Query requests conflicting access to resource `{}`",
+                self.resources.name(*id).unwrap_or("unknown")
+            );
+        }
+
+        access
+    }
+
+    /// Collects the `Access` of the query `P`, which may only use resources, as
+    /// `World::resource_system` does.
+    ///
+    /// # Panics
+    /// If `P` uses a component, or borrows the same resource more than once
+    pub fn resource_access<P>(&mut self) -> Access
+    where
+        for<'w> P: SystemParam<'w>,
+    {
+        let access = self.query_access::<P>();
+        assert!(
+            !access.uses_components(),
+            "Resource systems cannot use components, use `World::system` instead"
+        );
+        access
+    }
+
+    /// Collects the entities whose archetype contains every component required by `access`,
+    /// and none of the excluded ones.
+    ///
+    /// Matching archetypes are cached in `state`, so only archetypes created since the last call
+    /// are tested.
+    ///
+    /// # Returns
+    /// `Vec<Entity>` - The matching entities, in the buffer of `state`, which should be handed
+    /// back once the query has run
+    pub(crate) fn query_entities(&self, state: &mut QueryState, access: &Access) -> Vec<Entity> {
+        let mut entities = std::mem::take(&mut state.entities);
+
+        // Only sparse set components are required, so their entities are the only candidates.
+        if access.required().is_empty()
+            && let Some(set) = access
+                .sparse_required()
+                .iter()
+                .filter_map(|id| self.sparse_set(*id))
+                .min_by_key(|set| set.entities().len())
+        {
+            entities.extend(
+                set.entities()
+                    .iter()
+                    .filter(|entity| {
+                        self.location(entity).is_some_and(|location| {
+                            access.matches_archetype(
+                                self.archetypes[location.archetype].component_ids(),
+                            )
+                        })
+                    })
+                    .cloned(),
+            );
+            return entities;
+        }
+
+        for archetype in &self.archetypes[state.seen..] {
+            if access.matches_archetype(archetype.component_ids()) {
+                state.archetypes.push(archetype.id());
+            }
+        }
+        state.seen = self.archetypes.len();
+
+        entities.extend(
+            state
+                .archetypes
+                .iter()
+                .flat_map(|&id| self.archetypes[id].entities().iter().cloned()),
+        );
+        entities
+    }
+
+    /// Whether `entity` has every sparse set component required by `access`, and none of the
+    /// excluded ones. Table components are checked per archetype instead.
+    pub(crate) fn matches_sparse(&self, access: &Access, entity: &Entity) -> bool {
+        access
+            .sparse_required()
+            .iter()
+            .all(|id| self.has_component_id(entity, *id))
+            && !access
+                .sparse_excluded()
+                .iter()
+                .any(|id| self.has_component_id(entity, *id))
     }
 
     pub fn spawn_entity(&mut self) -> Entity {
         let entity = self.entity_registry.new_entity();
-        self.entities.insert(entity.clone(), HashMap::new());
+        let row = self.archetypes[EMPTY_ARCHETYPE].push_entity(entity.clone());
+        self.set_location(
+            &entity,
+            Some(EntityLocation {
+                archetype: EMPTY_ARCHETYPE,
+                row,
+            }),
+        );
         entity
     }
 
+    /// Whether `entity` is alive. Handles of despawned entities stay dead even after their id
+    /// is recycled, since the recycled entity has a newer generation.
+    pub fn is_alive(&self, entity: &Entity) -> bool {
+        self.entity_registry.is_alive(entity)
+    }
+
+    /// The number of entity ids that exhausted every generation and are no longer recycled.
+    pub fn retired_entity_ids(&self) -> usize {
+        self.entity_registry.retired()
+    }
+
+    /// Adds `component` to `entity`.
+    ///
+    /// # Panics
+    /// If the entity is not alive, or already has a component of this type. See
+    /// `World::try_add_component` for a non-panicking version.
     pub fn add_component<T: Component>(&mut self, entity: &Entity, component: T) {
-        let component_id = self.component_registry.id::<T>();
-
-        // If component already exists for that entity, panic
-        if self
-            .entities
-            .get(&entity)
-            .expect("Entity not found in World")
-            .contains_key(&component_id)
-        {
-            panic!("Entity already contains specified component");
+        if let Err(e) = self.try_add_component(entity, component) {
+            panic!("{e}");
         }
+    }
+
+    /// Adds `component` to `entity`.
+    ///
+    /// # Returns
+    /// `Result<(), EcsError>` - An error if the entity is not alive or already has a `T`
+    pub fn try_add_component<T: Component>(
+        &mut self,
+        entity: &Entity,
+        component: T,
+    ) -> Result<(), EcsError> {
+        let component_id = self.component_id::<T>();
+        self.try_add_component_with_id(entity, component_id, component)
+    }
 
-        // If component type hasn't been added yet, add it now.
-        if component_id as usize >= self.components.len() {
-            self.components.push(Box::new(ComponentList::<T>::new()));
+    /// Adds `component` to `entity` as the component registered under `component_id`, whose
+    /// lists must hold `T`s. Lets components registered at runtime share a Rust type.
+    ///
+    /// # Returns
+    /// `Result<(), EcsError>` - An error if the entity is not alive or already has the component
+    pub(crate) fn try_add_component_with_id<T: Component>(
+        &mut self,
+        entity: &Entity,
+        component_id: ComponentId,
+        component: T,
+    ) -> Result<(), EcsError> {
+        let Some(location) = self.location(entity) else {
+            return Err(EcsError::EntityNotFound(entity.clone()));
+        };
+
+        if self.has_component_id(entity, component_id) {
+            return Err(EcsError::ComponentAlreadyExists {
+                entity: entity.clone(),
+                component: self
+                    .component_registry
+                    .name(component_id)
+                    .unwrap_or("unknown"),
+            });
         }
 
-        // Get the ids of the entity before new component added. Use this as key for archetype for the entity.
-        let mut ids: Vec<ComponentId> = self
-            .entities
-            .get(&entity)
-            .expect("Entity not found in World")
-            .keys()
-            .cloned()
-            .collect();
+        if self.component_registry.storage_type(component_id) == Some(StorageType::Table) {
+            let to = self.archetype_with(location.archetype, component_id);
+            self.move_entity(entity, location, to, None);
+        }
+        self.push_component(entity, component_id, component);
+        self.trigger_hooks(HookKind::Add, component_id, entity);
+        self.trigger_hooks(HookKind::Insert, component_id, entity);
 
-        // Entity index is the length before inserting entity component.
-        let entity_index = self.components[component_id as usize].len();
-        self.components[component_id as usize].push_boxed(Box::new(component));
+        Ok(())
+    }
 
-        self.entity_lookup
-            .insert((component_id, entity_index), entity.clone());
+    /// Spawns an entity with every component of `bundle`, moving it straight into its final
+    /// archetype.
+    ///
+    /// # Panics
+    /// If the bundle contains the same component type more than once
+    pub fn spawn<B: Bundle>(&mut self, bundle: B) -> Entity {
+        let mut ids = vec![];
+        B::component_ids(self, &mut ids);
+        ids.sort_unstable();
+        if let Some(id) = ids.windows(2).find(|pair| pair[0] == pair[1]) {
+            panic!(
+                "Bundle contains component `{}` more than once",
+                self.component_registry.name(id[0]).unwrap_or("unknown")
+            );
+        }
+        let mut table_ids = ids.clone();
+        table_ids
+            .retain(|id| self.component_registry.storage_type(*id) == Some(StorageType::Table));
 
-        // Update entity with its component lookup
-        self.entities
-            .get_mut(&entity)
-            .expect("Entity not found in World")
-            .insert(component_id, entity_index);
+        let archetype = self.archetype_for(table_ids);
+        let entity = self.entity_registry.new_entity();
+        let row = self.archetypes[archetype].push_entity(entity.clone());
+        self.set_location(&entity, Some(EntityLocation { archetype, row }));
 
-        // If entity has an archetype lookup, update to the new archetype
-        if let Some(archetype_index) = self.archetype_lookup.get(&entity) {
-            let entities = self
-                .archetypes
-                .get_mut(&ids)
-                .expect("No entities found for Archetype");
-            entities.swap_remove(*archetype_index);
+        bundle.push_components(self, &entity);
+        if !self.hooks.is_empty() {
+            for kind in [HookKind::Add, HookKind::Insert] {
+                for id in &ids {
+                    self.trigger_hooks(kind, *id, &entity);
+                }
+            }
+        }
+        entity
+    }
 
-            // If a swapped entity exists at this index, need to update its lookup to reflect the changes
-            if *archetype_index < entities.len() {
-                let swapped_entity = entities[*archetype_index].clone();
-                self.archetype_lookup
-                    .insert(swapped_entity, *archetype_index);
+    /// Pushes `component` onto the column or sparse set that `entity` keeps its `T` in. The
+    /// entity must already be in the archetype with that column, and not have a `T` yet.
+    pub(crate) fn push_component<T: Component>(
+        &mut self,
+        entity: &Entity,
+        component_id: ComponentId,
+        component: T,
+    ) {
+        let tick = self.change_tick;
+        let storage_type = self
+            .component_registry
+            .storage_type(component_id)
+            .expect("Component not found in ComponentRegistry");
+        let column = match storage_type {
+            StorageType::Table => {
+                let location = self.location(entity).expect("Entity not found in World");
+                self.archetypes[location.archetype]
+                    .column_mut(component_id)
+                    .expect("Component not found in Archetype")
+            }
+            StorageType::SparseSet => {
+                let index = component_id as usize;
+                if index >= self.sparse_sets.len() {
+                    self.sparse_sets.resize_with(index + 1, || None);
+                }
+                let set = self.sparse_sets[index]
+                    .get_or_insert_with(|| SparseSet::new(Box::new(ComponentList::<T>::new())));
+                set.push_entity(entity.clone());
+                set.column_mut()
+            }
+        };
+
+        let list = column
+            .get_mut()
+            .as_any_mut()
+            .downcast_mut::<ComponentList<T>>()
+            .expect("Component type mismatch");
+        list.components.push(component);
+        list.ticks.push(ComponentTicks::new(tick));
+    }
+
+    /// Adds `component` to `entity`, replacing the existing one if the entity already has a `T`.
+    ///
+    /// # Returns
+    /// `Option<T>` - The component that was replaced, if any
+    ///
+    /// # Panics
+    /// If the entity is not found in the `World`
+    pub fn insert_component<T: Component>(&mut self, entity: &Entity, component: T) -> Option<T> {
+        match self.get_component_mut::<T>(entity) {
+            Some(existing) => {
+                let replaced = std::mem::replace(existing, component);
+                let id = self.component_id::<T>();
+                self.trigger_hooks(HookKind::Insert, id, entity);
+                Some(replaced)
+            }
+            None => {
+                self.add_component(entity, component);
+                None
             }
         }
+    }
+
+    /// Removes the `T` component from `entity`, moving the entity to its new archetype.
+    ///
+    /// # Returns
+    /// `Option<T>` - The removed component, or `None` if the entity did not have one or the
+    /// component is already being removed, i.e. this was called from one of its remove hooks
+    pub fn remove_component<T: Component>(&mut self, entity: &Entity) -> Option<T> {
+        let component_id = self.component_registry.get::<T>()?;
+        self.remove_component_with_id(entity, component_id)
+    }
+
+    /// Removes the component registered under `component_id`, whose lists must hold `T`s, from
+    /// `entity`.
+    ///
+    /// # Returns
+    /// `Option<T>` - The removed component, or `None` if the entity did not have one or the
+    /// component is already being removed, i.e. this was called from one of its remove hooks
+    pub(crate) fn remove_component_with_id<T: Component>(
+        &mut self,
+        entity: &Entity,
+        component_id: ComponentId,
+    ) -> Option<T> {
+        if !self.has_component_id(entity, component_id)
+            || self.removing.contains(&(entity.clone(), component_id))
+        {
+            return None;
+        }
+        self.trigger_remove_hooks(component_id, entity);
+        let component = self.take_component(entity, component_id);
 
-        // Finally update the ids to include the new component
-        ids.push(component_id);
+        if !self.removing.iter().any(|(other, _)| other == entity)
+            && let Some(index) = self.deferred_despawns.iter().position(|e| e == entity)
+        {
+            let entity = self.deferred_despawns.swap_remove(index);
+            let _ = self.try_despawn(entity);
+        }
+        component
+    }
+
+    /// Removes the component registered under `component_id`, which must be a `T`, from `entity`
+    /// without running any hooks.
+    fn take_component<T: Component>(
+        &mut self,
+        entity: &Entity,
+        component_id: ComponentId,
+    ) -> Option<T> {
+        let location = self.location(entity)?;
+
+        if self.component_registry.storage_type(component_id) == Some(StorageType::SparseSet) {
+            return self
+                .sparse_sets
+                .get_mut(component_id as usize)?
+                .as_mut()?
+                .take(entity);
+        }
 
-        // Add the updated entity to its new archetype
-        let entities = self.archetypes.entry(ids.clone()).or_insert(Vec::new());
+        let list = self.archetypes[location.archetype]
+            .column_mut(component_id)?
+            .get_mut()
+            .as_any_mut()
+            .downcast_mut::<ComponentList<T>>()
+            .expect("Component type mismatch");
+        list.ticks.swap_remove(location.row);
+        let component = list.components.swap_remove(location.row);
 
-        let archetype_index = entities.len();
-        entities.push(entity.clone());
+        let to = self.archetype_without(location.archetype, component_id);
+        self.move_entity(entity, location, to, Some(component_id));
 
-        self.archetype_lookup
-            .insert(entity.clone(), archetype_index);
+        Some(component)
     }
 
+    /// Despawns `entity`, dropping all of its components.
+    ///
+    /// # Panics
+    /// If the entity is not alive. See `World::try_despawn` for a non-panicking version.
     pub fn despawn_entity(&mut self, entity: Entity) {
-        let map = self
-            .entities
-            .get_mut(&entity)
-            .expect("Entity not found in world");
+        if let Err(e) = self.try_despawn(entity) {
+            panic!("{e}");
+        }
+    }
 
-        let ids: Vec<ComponentId> = map.keys().cloned().collect();
+    /// Despawns `entity`, dropping all of its components. Its children are detached rather than
+    /// despawned, see `World::despawn_recursive`.
+    ///
+    /// From a remove hook of one of its components, the entity is only despawned once that
+    /// component has been removed.
+    ///
+    /// # Returns
+    /// `Result<(), EcsError>` - An error if the entity is not alive
+    pub fn try_despawn(&mut self, entity: Entity) -> Result<(), EcsError> {
+        if !self.is_alive(&entity) {
+            return Err(EcsError::EntityNotFound(entity));
+        }
+        if self.removing.iter().any(|(other, _)| *other == entity) {
+            if !self.deferred_despawns.contains(&entity) {
+                self.deferred_despawns.push(entity);
+            }
+            return Ok(());
+        }
 
-        // Remove entity from Archetypes
-        if let Some(archetype_index) = self.archetype_lookup.get(&entity) {
-            let archetypes = self
-                .archetypes
-                .get_mut(&ids)
-                .expect("Archetype not found for entity");
+        // Detaching can move the entity to another archetype, so it has to happen first.
+        self.detach_hierarchy(&entity);
+        // The entity is despawned below, whether or not a hook asked for it.
+        self.trigger_despawn_hooks(&entity);
+        let Some(location) = self.location(&entity) else {
+            return Ok(());
+        };
 
-            archetypes.swap_remove(*archetype_index);
+        for set in self.sparse_sets.iter_mut().flatten() {
+            set.remove(&entity);
+        }
 
-            // Update swapped entities archetype position
-            if *archetype_index < archetypes.len() {
-                let swapped_entity = archetypes[*archetype_index].clone();
-                self.archetype_lookup
-                    .insert(swapped_entity, *archetype_index);
-            }
+        let swapped = self.archetypes[location.archetype].remove_row(location.row);
+        if let Some(swapped) = swapped {
+            self.set_location(&swapped, Some(location));
         }
+        self.set_location(&entity, None);
 
-        self.archetype_lookup.remove(&entity);
+        self.entity_registry.remove_entity(&entity);
 
-        // Remove Entities Components
+        Ok(())
+    }
 
-        let mut entities_to_update: Vec<(Entity, ComponentId, EntityIndex)> = Vec::new();
+    /// Lets the components of `T` be included in a `WorldSnapshot`. Every component type with
+    /// live components has to be registered before `World::snapshot` is called.
+    pub fn register_snapshot<T: Component + Clone + PartialEq>(&mut self) {
+        self.component_registry.register_snapshot::<T>();
+    }
 
-        for id in ids {
-            let entity_index = map.get(&id).expect("Component not found for Entity");
-            self.components[id as usize].swap_remove(*entity_index);
-            let old_index = self.components[id as usize].len();
-            if old_index == 0 {
+    /// Copies every entity, generation and component, so the `World` can later be rewound with
+    /// `World::restore`. Resources are not included.
+    ///
+    /// # Returns
+    /// `Result<WorldSnapshot, EcsError>` - The snapshot, or an error if a component type was not
+    /// registered with `World::register_snapshot`
+    pub fn snapshot(&self) -> Result<WorldSnapshot, EcsError> {
+        let archetypes = self
+            .archetypes
+            .iter()
+            .map(|archetype| archetype.snapshot(&self.component_registry))
+            .collect::<Result<_, _>>()?;
+        let sparse_sets = self
+            .sparse_sets
+            .iter()
+            .enumerate()
+            .map(|(id, set)| {
+                set.as_ref()
+                    .map(|set| set.snapshot(id as ComponentId, &self.component_registry))
+                    .transpose()
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(WorldSnapshot {
+            entity_registry: self.entity_registry.clone(),
+            locations: self.locations.clone(),
+            archetypes,
+            sparse_sets,
+        })
+    }
+
+    /// Rewinds every entity and component to `snapshot`, so the same `Entity` handles are alive
+    /// with the same values. Resources are left alone and hooks are not run.
+    ///
+    /// Every restored component counts as added and changed, so change filters see them.
+    ///
+    /// # Panics
+    /// If `snapshot` was taken from a different `World`
+    pub fn restore(&mut self, snapshot: &WorldSnapshot) {
+        assert!(
+            snapshot.archetypes.len() <= self.archetypes.len()
+                && snapshot.sparse_sets.len() <= self.sparse_sets.len(),
+            "Snapshot was taken from a different World"
+        );
+
+        let tick = self.change_tick;
+        self.entity_registry = snapshot.entity_registry.clone();
+        self.locations = snapshot.locations.clone();
+
+        for (index, archetype) in self.archetypes.iter_mut().enumerate() {
+            match snapshot.archetypes.get(index) {
+                Some(captured) => archetype.restore(captured, tick),
+                None => archetype.clear(),
+            }
+        }
+        for (index, set) in self.sparse_sets.iter_mut().enumerate() {
+            let Some(set) = set else {
                 continue;
+            };
+            match snapshot.sparse_sets.get(index).and_then(Option::as_ref) {
+                Some(captured) => set.restore(captured, tick),
+                None => set.clear(),
+            }
+        }
+    }
+
+    /// Runs the remove hooks of every component of `entity`, which is about to leave the `World`.
+    ///
+    /// # Returns
+    /// `bool` - Whether a hook despawned the entity, which is left to the caller
+    pub(crate) fn trigger_despawn_hooks(&mut self, entity: &Entity) -> bool {
+        if self.hooks.is_empty() {
+            return false;
+        }
+        self.despawning.push((entity.clone(), Vec::new()));
+        for id in self.component_ids_of(entity) {
+            // An earlier hook may have removed the component already.
+            if self.has_component_id(entity, id) {
+                self.trigger_remove_hooks(id, entity);
             }
+        }
+        self.despawning
+            .retain(|(despawning, _)| despawning != entity);
 
-            // If entity swapped to new index, update it everywhere.
-            let updating_entity = self
-                .entity_lookup
-                .get(&(id, old_index))
-                .expect("Entity not found for Component")
-                .clone();
+        let despawned = self.deferred_despawns.contains(entity);
+        self.deferred_despawns.retain(|deferred| deferred != entity);
+        despawned
+    }
 
-            entities_to_update.push((updating_entity.clone(), id, *entity_index));
+    /// Removes `entity` and moves each of its components into a list of its own, without running
+    /// any hooks.
+    ///
+    /// # Returns
+    /// `Option<Vec<TakenComponent>>` - The components, or `None` if the entity is not alive
+    pub(crate) fn take_entity(&mut self, entity: &Entity) -> Option<Vec<TakenComponent>> {
+        let location = self.location(entity)?;
 
-            self.entity_lookup.remove(&(id, old_index));
-            self.entity_lookup
-                .insert((id, *entity_index), updating_entity);
+        let (mut taken, swapped) = self.archetypes[location.archetype].take_row(location.row);
+        for (id, set) in self.sparse_sets.iter_mut().enumerate() {
+            if let Some(set) = set
+                && let Some(list) = set.take_row(entity)
+            {
+                taken.push(TakenComponent {
+                    id: id as ComponentId,
+                    list,
+                });
+            }
         }
 
-        // Update entities that have been victims of swapping
-        for (entity, id, index) in entities_to_update {
-            self.entities
-                .get_mut(&entity)
-                .expect("Entity not found in World")
-                .insert(id, index);
+        if let Some(swapped) = swapped {
+            self.set_location(&swapped, Some(location));
         }
+        self.set_location(entity, None);
+        self.entity_registry.remove_entity(entity);
+
+        Some(taken)
+    }
 
-        self.entities.remove(&entity);
+    /// Spawns an entity with components taken from a `World` whose component registry is `from`,
+    /// running their add and insert hooks.
+    pub(crate) fn spawn_taken(
+        &mut self,
+        from: &ComponentRegistry,
+        components: Vec<TakenComponent>,
+    ) -> Entity {
+        let mut components: Vec<(ComponentId, Box<dyn ComponentListOps>)> = components
+            .into_iter()
+            .map(|taken| (self.component_registry.import(from, taken.id), taken.list))
+            .collect();
 
-        self.entity_registry.remove_entity(entity);
+        let mut table_ids: Vec<ComponentId> = components
+            .iter()
+            .map(|(id, _)| *id)
+            .filter(|id| self.component_registry.storage_type(*id) == Some(StorageType::Table))
+            .collect();
+        table_ids.sort_unstable();
+
+        let archetype = self.archetype_for(table_ids);
+        let entity = self.entity_registry.new_entity();
+        let row = self.archetypes[archetype].push_entity(entity.clone());
+        self.set_location(&entity, Some(EntityLocation { archetype, row }));
+
+        let tick = self.change_tick;
+        for (id, list) in &mut components {
+            list.ticks_mut().fill(ComponentTicks::new(tick));
+            let column = match self.component_registry.storage_type(*id) {
+                Some(StorageType::SparseSet) => {
+                    let index = *id as usize;
+                    if index >= self.sparse_sets.len() {
+                        self.sparse_sets.resize_with(index + 1, || None);
+                    }
+                    let set = self.sparse_sets[index].get_or_insert_with(|| {
+                        SparseSet::new(
+                            self.component_registry
+                                .new_list(*id)
+                                .expect("Component not found in ComponentRegistry"),
+                        )
+                    });
+                    set.push_entity(entity.clone());
+                    set.column_mut()
+                }
+                _ => self.archetypes[archetype]
+                    .column_mut(*id)
+                    .expect("Component not found in Archetype"),
+            };
+            list.move_row(0, column.get_mut());
+        }
+
+        if !self.hooks.is_empty() {
+            for kind in [HookKind::Add, HookKind::Insert] {
+                for (id, _) in &components {
+                    self.trigger_hooks(kind, *id, &entity);
+                }
+            }
+        }
+        entity
+    }
+
+    /// The ids of every component `entity` has, table components first.
+    pub(crate) fn component_ids_of(&self, entity: &Entity) -> Vec<ComponentId> {
+        let Some(location) = self.location(entity) else {
+            return vec![];
+        };
+        let mut ids = self.archetypes[location.archetype].component_ids().to_vec();
+        for (id, set) in self.sparse_sets.iter().enumerate() {
+            if let Some(set) = set
+                && set.row(entity).is_some()
+            {
+                ids.push(id as ComponentId);
+            }
+        }
+        ids
+    }
+
+    fn set_location(&mut self, entity: &Entity, location: Option<EntityLocation>) {
+        let index = entity.id() as usize;
+        if index >= self.locations.len() {
+            self.locations.resize(index + 1, None);
+        }
+        self.locations[index] = location;
+    }
+
+    /// The archetype with the components of `from` plus `id`, creating it if it does not exist yet.
+    fn archetype_with(&mut self, from: ArchetypeId, id: ComponentId) -> ArchetypeId {
+        if let Some(to) = self.archetypes[from].add_edge(id) {
+            return to;
+        }
+
+        let mut ids = self.archetypes[from].component_ids().to_vec();
+        let position = ids.partition_point(|existing| *existing < id);
+        ids.insert(position, id);
+
+        let to = self.archetype_for(ids);
+        self.archetypes[from].set_add_edge(id, to);
+        self.archetypes[to].set_remove_edge(id, from);
+        to
+    }
+
+    /// The archetype with the components of `from` minus `id`, creating it if it does not exist yet.
+    fn archetype_without(&mut self, from: ArchetypeId, id: ComponentId) -> ArchetypeId {
+        if let Some(to) = self.archetypes[from].remove_edge(id) {
+            return to;
+        }
+
+        let ids = self.archetypes[from]
+            .component_ids()
+            .iter()
+            .copied()
+            .filter(|existing| *existing != id)
+            .collect();
+
+        let to = self.archetype_for(ids);
+        self.archetypes[from].set_remove_edge(id, to);
+        self.archetypes[to].set_add_edge(id, from);
+        to
+    }
+
+    /// The archetype with exactly the sorted table components `ids`, creating it if it does not
+    /// exist yet.
+    fn archetype_for(&mut self, ids: Vec<ComponentId>) -> ArchetypeId {
+        if let Some(&id) = self.archetype_index.get(&ids) {
+            return id;
+        }
+
+        let columns = ids
+            .iter()
+            .map(|id| {
+                self.component_registry
+                    .new_list(*id)
+                    .expect("Component not registered")
+            })
+            .collect();
+
+        let id = self.archetypes.len();
+        self.archetype_index.insert(ids.clone(), id);
+        self.archetypes.push(Archetype::new(id, ids, columns));
+        id
+    }
+
+    /// Moves `entity` from the archetype at `location` to the end of the archetype `to`, dropping
+    /// the components `to` has no column for. The `skip` column must already have had the
+    /// entity's component taken out.
+    fn move_entity(
+        &mut self,
+        entity: &Entity,
+        location: EntityLocation,
+        to: ArchetypeId,
+        skip: Option<ComponentId>,
+    ) {
+        let (source, target) = if location.archetype < to {
+            let (left, right) = self.archetypes.split_at_mut(to);
+            (&mut left[location.archetype], &mut right[0])
+        } else {
+            let (left, right) = self.archetypes.split_at_mut(location.archetype);
+            (&mut right[0], &mut left[to])
+        };
+
+        let swapped = source.move_row(location.row, target, skip);
+        let row = target.push_entity(entity.clone());
+
+        if let Some(swapped) = swapped {
+            self.set_location(&swapped, Some(location));
+        }
+        self.set_location(entity, Some(EntityLocation { archetype: to, row }));
     }
 }
 
@@ -230,13 +1311,6 @@ impl World {
 macro_rules! spawn_entity {
     ($world:expr, ($($component:expr),+ $(,)?)) => {{
         let world: &mut ecs_core::World = &mut $world;
-
-        let entity = world.spawn_entity();
-
-        $(
-            world.add_component(&entity, $component);
-        )+
-
-        entity
+        world.spawn(($($component,)+))
     }};
 }
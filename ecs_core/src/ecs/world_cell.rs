@@ -0,0 +1,158 @@
+use std::marker::PhantomData;
+
+use crate::{
+    Component, ComponentList, ComponentTicks, Entity, Mut, Resource, Tick, World, ecs::EventCursors,
+};
+
+/// A handle to a `World` that lets several `SystemParam`s borrow disjoint parts of it at once.
+///
+/// Creating one requires `&mut World`, so nothing else can touch the world while it is in use.
+/// Every accessor is `unsafe`: the caller must make sure no two live borrows overlap, which
+/// `World::system` does by validating the `Access` of a query before fetching anything.
+///
+/// The cell also carries the ticks of the system run it was created for, which change filters
+/// compare against and mutable borrows are stamped with, and the `EventReader` cursors of the
+/// running system.
+#[derive(Clone, Copy)]
+pub struct UnsafeWorldCell<'w> {
+    world: *mut World,
+    last_run: Tick,
+    this_run: Tick,
+    event_cursors: Option<&'w EventCursors>,
+    marker: PhantomData<&'w World>,
+}
+
+// SAFETY: `World` is `Sync`, and every accessor already requires the caller to rule out
+// overlapping borrows, including ones made from other threads.
+unsafe impl Send for UnsafeWorldCell<'_> {}
+unsafe impl Sync for UnsafeWorldCell<'_> {}
+
+impl<'w> UnsafeWorldCell<'w> {
+    pub(crate) fn new(world: &'w mut World, last_run: Tick, this_run: Tick) -> Self {
+        Self {
+            world,
+            last_run,
+            this_run,
+            event_cursors: None,
+            marker: PhantomData,
+        }
+    }
+
+    /// The same cell, for a system whose `EventReader`s use `cursors`.
+    pub(crate) fn with_event_cursors<'s>(self, cursors: &'s EventCursors) -> UnsafeWorldCell<'s>
+    where
+        'w: 's,
+    {
+        UnsafeWorldCell {
+            event_cursors: Some(cursors),
+            ..self
+        }
+    }
+
+    /// The same cell, for a system that last ran at `last_run`.
+    pub(crate) fn with_last_run(self, last_run: Tick) -> Self {
+        Self { last_run, ..self }
+    }
+
+    /// The same cell, for a system run stamped with `this_run`.
+    pub(crate) fn with_this_run(self, this_run: Tick) -> Self {
+        Self { this_run, ..self }
+    }
+
+    /// The tick at which the running system last ran.
+    pub fn last_run(self) -> Tick {
+        self.last_run
+    }
+
+    /// The tick of the current system run.
+    pub fn this_run(self) -> Tick {
+        self.this_run
+    }
+
+    pub(crate) fn event_cursors(self) -> Option<&'w EventCursors> {
+        self.event_cursors
+    }
+
+    /// # Safety
+    /// The returned reference must not be used to read a component that is mutably borrowed
+    /// through this cell.
+    pub unsafe fn world(self) -> &'w World {
+        unsafe { &*self.world }
+    }
+
+    /// # Safety
+    /// Nothing else may borrow any part of the world for `'w`.
+    pub unsafe fn world_mut(self) -> &'w mut World {
+        unsafe { &mut *self.world }
+    }
+
+    /// Borrows a component of `entity` without borrowing the rest of the `World`.
+    ///
+    /// # Safety
+    /// No mutable borrow of the `T` components may be live for `'w`.
+    pub unsafe fn get_component<T: Component>(self, entity: &Entity) -> Option<&'w T> {
+        let world = unsafe { self.world() };
+        let id = world.component_registry().get::<T>()?;
+        let (column, row) = world.component_column(entity, id)?;
+
+        column
+            .get()
+            .as_any()
+            .downcast_ref::<ComponentList<T>>()?
+            .components
+            .get(row)
+    }
+
+    /// Borrows the `R` resource without borrowing the rest of the `World`.
+    ///
+    /// # Safety
+    /// No mutable borrow of the `R` resource may be live for `'w`.
+    pub unsafe fn get_resource<R: Resource>(self) -> Option<&'w R> {
+        unsafe { self.world() }.resources().get::<R>()
+    }
+
+    /// Mutably borrows the `R` resource without borrowing the rest of the `World`.
+    ///
+    /// # Safety
+    /// No other borrow of the `R` resource may be live for `'w`.
+    pub unsafe fn get_resource_mut<R: Resource>(self) -> Option<&'w mut R> {
+        unsafe { self.world().resources().get_unchecked_mut::<R>() }
+    }
+
+    /// Reads the change ticks of the `T` component of `entity`.
+    ///
+    /// # Safety
+    /// No mutable borrow of the `T` components may be live.
+    pub unsafe fn get_component_ticks<T: Component>(
+        self,
+        entity: &Entity,
+    ) -> Option<ComponentTicks> {
+        let world = unsafe { self.world() };
+        let id = world.component_registry().get::<T>()?;
+        let (column, row) = world.component_column(entity, id)?;
+
+        column.get().ticks().get(row).copied()
+    }
+
+    /// Mutably borrows a component of `entity` without borrowing the rest of the `World`.
+    ///
+    /// The component is only marked as changed once the returned `Mut` is written to.
+    ///
+    /// # Safety
+    /// No other borrow of the `T` components may be live for `'w`.
+    pub unsafe fn get_component_mut<T: Component>(self, entity: &Entity) -> Option<Mut<'w, T>> {
+        let world = unsafe { self.world() };
+        let id = world.component_registry().get::<T>()?;
+        let (column, row) = world.component_column(entity, id)?;
+
+        let list = unsafe { column.get_unchecked_mut() }
+            .as_any_mut()
+            .downcast_mut::<ComponentList<T>>()?;
+        Some(Mut::new(
+            list.components.get_mut(row)?,
+            list.ticks.get_mut(row)?,
+            self.last_run,
+            self.this_run,
+        ))
+    }
+}
@@ -1,2 +1,3 @@
 mod ecs;
 pub use ecs::*;
+pub use ecs_core_derive::{Bundle, Component, Resource};
@@ -0,0 +1,50 @@
+use ecs_core::{CommandQueue, Commands, Component, Res, ResMut, Resource, World};
+
+struct A;
+impl Component for A {}
+
+struct B;
+impl Component for B {}
+
+struct R;
+impl Resource for R {}
+
+#[test]
+#[should_panic(expected = "conflicting access to component")]
+fn two_mutable_borrows_conflict() {
+    World::new().query_access::<(&mut A, &mut A)>();
+}
+
+#[test]
+#[should_panic(expected = "conflicting access to component")]
+fn shared_and_mutable_borrow_conflict() {
+    World::new().query_access::<(&A, &mut A)>();
+}
+
+#[test]
+#[should_panic(expected = "conflicting access to component")]
+fn optional_mutable_borrow_conflicts() {
+    World::new().query_access::<(&mut A, Option<&mut A>)>();
+}
+
+#[test]
+#[should_panic(expected = "conflicting access to resource")]
+fn shared_and_mutable_resource_conflict() {
+    World::new().query_access::<(Res<R>, ResMut<R>)>();
+}
+
+#[test]
+#[should_panic(expected = "conflicting access to resource")]
+fn commands_conflict_with_their_queue() {
+    World::new().query_access::<(Commands, ResMut<CommandQueue>)>();
+}
+
+#[test]
+fn shared_and_disjoint_borrows_are_allowed() {
+    let mut world = World::new();
+    assert!(world.query_access::<(&A, &A)>().conflicts().is_empty());
+
+    let access = world.query_access::<(&A, Option<&mut B>)>();
+    assert_eq!(access.writes().len(), 1);
+    assert!(access.conflicts().is_empty());
+}
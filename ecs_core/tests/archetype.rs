@@ -0,0 +1,67 @@
+use ecs_core::{Component, Entity, World};
+
+#[derive(Debug, PartialEq)]
+struct A(u32);
+impl Component for A {}
+
+#[derive(Debug, PartialEq)]
+struct B(u32);
+impl Component for B {}
+
+fn spawn(world: &mut World, count: u32) -> Vec<Entity> {
+    (0..count)
+        .map(|i| {
+            let entity = world.spawn_entity();
+            world.add_component(&entity, A(i));
+            entity
+        })
+        .collect()
+}
+
+#[test]
+fn rows_survive_moves_between_archetypes() {
+    let mut world = World::new();
+    let entities = spawn(&mut world, 12);
+    for (i, entity) in entities.iter().enumerate() {
+        if i % 2 == 0 {
+            world.add_component(entity, B(i as u32));
+        }
+        if i % 3 == 0 {
+            world.remove_component::<A>(entity);
+        }
+    }
+    world.despawn_entity(entities[4].clone());
+    world.despawn_entity(entities[7].clone());
+
+    for (i, entity) in entities.iter().enumerate() {
+        if i == 4 || i == 7 {
+            assert!(!world.is_alive(entity));
+            continue;
+        }
+        let a = (i % 3 != 0).then_some(A(i as u32));
+        let b = (i % 2 == 0).then_some(B(i as u32));
+        assert_eq!(world.get_component::<A>(entity), a.as_ref(), "entity {i}");
+        assert_eq!(world.get_component::<B>(entity), b.as_ref(), "entity {i}");
+    }
+}
+
+#[test]
+fn queries_visit_every_matching_archetype() {
+    let mut world = World::new();
+    let entities = spawn(&mut world, 6);
+    for entity in &entities[3..] {
+        world.add_component(entity, B(0));
+    }
+    let empty = world.spawn_entity();
+
+    let mut with_a = vec![];
+    world.system::<&A, _>(|_, a| with_a.push(a.0));
+    with_a.sort();
+    assert_eq!(with_a, [0, 1, 2, 3, 4, 5]);
+
+    // Entities without components are in an archetype too, so optional queries see them.
+    let mut all = vec![];
+    world.system::<Option<&B>, _>(|entity, b| all.push((entity, b.is_some())));
+    assert_eq!(all.len(), 7);
+    assert!(all.contains(&(empty, false)));
+}
@@ -0,0 +1,47 @@
+use ecs_core::{Component, StorageType, World};
+
+#[derive(Debug, PartialEq)]
+struct A(u32);
+impl Component for A {}
+
+#[derive(Debug, PartialEq)]
+struct B(u32);
+impl Component for B {}
+
+#[derive(Debug, PartialEq)]
+struct Marker;
+impl Component for Marker {
+    const STORAGE_TYPE: StorageType = StorageType::SparseSet;
+}
+
+#[test]
+fn spawn_adds_every_component_of_the_bundle() {
+    let mut world = World::new();
+    let first = world.spawn((A(1), B(1)));
+    // Nested tuples are bundles too.
+    let second = world.spawn(((B(2), Marker), A(2)));
+    let single = world.spawn(A(3));
+
+    assert_eq!(world.get_component(&first), Some(&A(1)));
+    assert_eq!(world.get_component(&first), Some(&B(1)));
+    assert_eq!(world.get_component::<Marker>(&first), None);
+    assert_eq!(world.get_component(&second), Some(&A(2)));
+    assert_eq!(world.get_component(&second), Some(&B(2)));
+    assert_eq!(world.get_component(&second), Some(&Marker));
+    assert_eq!(world.get_component::<B>(&single), None);
+
+    // Both bundles end up in the same archetype as entities built one component at a time.
+    let built = world.spawn_entity();
+    world.add_component(&built, B(4));
+    world.add_component(&built, A(4));
+    let mut seen = vec![];
+    world.system::<(&A, &B), _>(|_, (a, b)| seen.push((a.0, b.0)));
+    seen.sort();
+    assert_eq!(seen, [(1, 1), (2, 2), (4, 4)]);
+}
+
+#[test]
+#[should_panic(expected = "more than once")]
+fn duplicate_components_are_rejected() {
+    World::new().spawn((A(1), B(1), A(2)));
+}
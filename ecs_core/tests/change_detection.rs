@@ -0,0 +1,75 @@
+use ecs_core::{Added, Changed, Component, Entity, World};
+
+struct A(u32);
+impl Component for A {}
+
+fn spawn_a(world: &mut World, value: u32) -> Entity {
+    let entity = world.spawn_entity();
+    world.add_component(&entity, A(value));
+    entity
+}
+
+fn count_changed(world: &mut World) -> usize {
+    let mut count = 0;
+    world.system::<Changed<A>, _>(|_, _| count += 1);
+    count
+}
+
+#[test]
+fn each_call_site_tracks_its_own_changes() {
+    let mut world = World::new();
+    spawn_a(&mut world, 0);
+
+    let mut first = 0;
+    world.system::<Changed<A>, _>(|_, _| first += 1);
+    let mut second = 0;
+    world.system::<Changed<A>, _>(|_, _| second += 1);
+    assert_eq!((first, second), (1, 1));
+}
+
+#[test]
+fn a_call_site_sees_changes_since_it_last_ran() {
+    let mut world = World::new();
+    let entity = spawn_a(&mut world, 0);
+
+    assert_eq!(count_changed(&mut world), 1);
+    assert_eq!(count_changed(&mut world), 0);
+
+    world.get_component_mut::<A>(&entity).unwrap().0 = 1;
+    assert_eq!(count_changed(&mut world), 1);
+}
+
+#[test]
+fn added_only_matches_new_components() {
+    let mut world = World::new();
+    spawn_a(&mut world, 0);
+
+    let mut added = Vec::new();
+    for value in 1..3 {
+        let entity = spawn_a(&mut world, value);
+        world.get_component_mut::<A>(&entity).unwrap();
+
+        let mut values = Vec::new();
+        world.system::<(Added<A>, &A), _>(|_, (_, a)| values.push(a.0));
+        values.sort();
+        added.push(values);
+    }
+    assert_eq!(added, vec![vec![0, 1], vec![2]]);
+}
+
+#[test]
+fn only_writes_through_a_query_mark_changes() {
+    let mut world = World::new();
+    let written = spawn_a(&mut world, 0);
+    spawn_a(&mut world, 0);
+    assert_eq!(count_changed(&mut world), 2);
+
+    world.system::<&mut A, _>(|entity, mut a| {
+        if entity == written {
+            a.0 += 1;
+        } else {
+            assert_eq!(a.0, 0);
+        }
+    });
+    assert_eq!(count_changed(&mut world), 1);
+}
@@ -0,0 +1,63 @@
+use ecs_core::{Commands, Component, Entity, World};
+
+#[derive(Debug, PartialEq)]
+struct A(u32);
+impl Component for A {}
+
+#[derive(Debug, PartialEq)]
+struct B(u32);
+impl Component for B {}
+
+fn world() -> (World, Vec<Entity>) {
+    let mut world = World::new();
+    let entities = (0..3)
+        .map(|i| {
+            let entity = world.spawn_entity();
+            world.add_component(&entity, A(i));
+            entity
+        })
+        .collect();
+    (world, entities)
+}
+
+#[test]
+fn commands_apply_once_the_system_has_run() {
+    let (mut world, entities) = world();
+    let mut seen = 0;
+    world.system::<(&A, Commands), _>(|entity, (a, mut commands)| {
+        seen += 1;
+        match a.0 {
+            0 => {
+                commands.spawn().insert(A(10)).insert(B(10));
+            }
+            1 => {
+                commands.entity(&entity).insert(B(1));
+            }
+            _ => commands.despawn(&entity),
+        }
+    });
+    assert_eq!(seen, 3);
+
+    assert_eq!(world.get_component(&entities[1]), Some(&B(1)));
+    assert!(!world.is_alive(&entities[2]));
+    let mut spawned = vec![];
+    world.system::<(&A, &B), _>(|_, (a, b)| spawned.push((a.0, b.0)));
+    spawned.sort();
+    assert_eq!(spawned, [(1, 1), (10, 10)]);
+}
+
+#[test]
+fn commands_on_despawned_entities_are_skipped() {
+    let (mut world, entities) = world();
+    let target = entities[0].clone();
+    world.system::<(&A, Commands), _>(move |_, (a, mut commands)| {
+        if a.0 == 2 {
+            commands.despawn(&target);
+            commands.entity(&target).insert(B(0)).remove::<A>();
+            commands.despawn(&target);
+        }
+    });
+
+    assert!(!world.is_alive(&entities[0]));
+    assert_eq!(world.get_component(&entities[1]), Some(&A(1)));
+}
@@ -0,0 +1,87 @@
+use ecs_core::{Bundle, Component, EcsError, Reflect, ReflectValue, Resource, StorageType, World};
+use serde::{Deserialize, Serialize};
+
+#[derive(Component, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[component(reflect)]
+struct Position {
+    x: f32,
+    y: f32,
+}
+
+#[derive(Component, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[component(reflect)]
+struct Name(String);
+
+#[derive(Component, Debug, PartialEq)]
+#[component(storage = "SparseSet")]
+struct Selected;
+
+#[derive(Bundle)]
+struct Player {
+    position: Position,
+    name: Name,
+}
+
+#[derive(Resource, Default)]
+struct Score(u32);
+
+#[test]
+fn derived_components_keep_their_storage_type() {
+    assert_eq!(Position::STORAGE_TYPE, StorageType::Table);
+    assert_eq!(Selected::STORAGE_TYPE, StorageType::SparseSet);
+}
+
+#[test]
+fn derived_bundles_and_resources_work_with_the_world() {
+    let mut world = World::new();
+    world.insert_resource(Score::default());
+    let entity = world.spawn((
+        Player {
+            position: Position { x: 1.0, y: 2.0 },
+            name: Name("crate".to_string()),
+        },
+        Selected,
+    ));
+
+    assert_eq!(
+        world.get_component(&entity),
+        Some(&Position { x: 1.0, y: 2.0 })
+    );
+    assert_eq!(
+        world.get_component(&entity),
+        Some(&Name("crate".to_string()))
+    );
+    assert_eq!(world.get_component(&entity), Some(&Selected));
+
+    world.resource_mut::<Score>().unwrap().0 += 1;
+    assert_eq!(world.resource::<Score>().unwrap().0, 1);
+}
+
+#[test]
+fn reflected_fields_are_read_and_written_by_name() {
+    assert_eq!(Position::field_names(), &["x", "y"]);
+    assert_eq!(Name::field_names(), &["0"]);
+
+    let mut position = Position { x: 1.0, y: 2.0 };
+    assert_eq!(position.field("y"), Ok(ReflectValue::from(2.0f32)));
+    position.set_field("x", ReflectValue::from(5.0f32)).unwrap();
+    assert_eq!(position, Position { x: 5.0, y: 2.0 });
+
+    let mut name = Name("crate".to_string());
+    name.set_field("0", ReflectValue::from("box")).unwrap();
+    assert_eq!(name, Name("box".to_string()));
+}
+
+#[test]
+fn invalid_reflected_fields_are_rejected() {
+    let mut position = Position { x: 1.0, y: 2.0 };
+    assert!(matches!(
+        position.field("z"),
+        Err(EcsError::UnknownField { .. })
+    ));
+    assert!(matches!(
+        position.set_field("x", ReflectValue::from("left")),
+        Err(EcsError::Reflect(_))
+    ));
+    assert_eq!(position, Position { x: 1.0, y: 2.0 });
+}
@@ -0,0 +1,137 @@
+use ecs_core::{
+    Component, Diagnostics, QuerySystem, ResMut, Resource, ResourceSystem, Schedule, Stage,
+    StorageType, World,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Component, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[component(reflect)]
+struct Position {
+    x: f32,
+    y: f32,
+}
+
+#[derive(Component)]
+struct Velocity(f32);
+
+#[derive(Component)]
+#[component(storage = "SparseSet")]
+struct Selected;
+
+#[derive(Default)]
+struct Frames(u32);
+impl Resource for Frames {}
+
+/// The number of `name` components, by the end of the type name.
+fn count(diagnostics: &Diagnostics, name: &str) -> usize {
+    diagnostics
+        .components
+        .iter()
+        .find(|component| component.name.ends_with(name))
+        .map_or(0, |component| component.count)
+}
+
+/// The entity counts of the archetypes that are not empty.
+fn archetype_sizes(diagnostics: &Diagnostics) -> Vec<usize> {
+    let mut sizes: Vec<usize> = diagnostics
+        .archetypes
+        .iter()
+        .map(|archetype| archetype.entity_count)
+        .filter(|&count| count > 0)
+        .collect();
+    sizes.sort();
+    sizes
+}
+
+#[test]
+fn counts_follow_spawns_despawns_and_archetype_moves() {
+    let mut world = World::new();
+    let a = world.spawn((Position { x: 0.0, y: 0.0 }, Velocity(1.0)));
+    let b = world.spawn(Position { x: 1.0, y: 0.0 });
+    let c = world.spawn(Position { x: 2.0, y: 0.0 });
+    world.add_component(&c, Selected);
+
+    let diagnostics = world.diagnostics();
+    assert_eq!(diagnostics.entity_count, 3);
+    assert_eq!(archetype_sizes(&diagnostics), [1, 2]);
+    assert_eq!(count(&diagnostics, "Position"), 3);
+    assert_eq!(count(&diagnostics, "Velocity"), 1);
+    assert_eq!(count(&diagnostics, "Selected"), 1);
+    let selected = diagnostics
+        .components
+        .iter()
+        .find(|component| component.name.ends_with("Selected"))
+        .unwrap();
+    assert_eq!(selected.storage_type, StorageType::SparseSet);
+
+    // Moving `b` into the archetype of `a` leaves its old archetype with only `c`.
+    world.add_component(&b, Velocity(2.0));
+    let diagnostics = world.diagnostics();
+    assert_eq!(diagnostics.entity_count, 3);
+    assert_eq!(archetype_sizes(&diagnostics), [1, 2]);
+    assert_eq!(count(&diagnostics, "Velocity"), 2);
+
+    world.remove_component::<Velocity>(&a);
+    world.remove_component::<Selected>(&c);
+    let diagnostics = world.diagnostics();
+    assert_eq!(archetype_sizes(&diagnostics), [1, 2]);
+    assert_eq!(count(&diagnostics, "Velocity"), 1);
+    assert_eq!(count(&diagnostics, "Selected"), 0);
+
+    world.despawn_entity(a);
+    world.despawn_entity(b);
+    let diagnostics = world.diagnostics();
+    assert_eq!(diagnostics.entity_count, 1);
+    assert_eq!(archetype_sizes(&diagnostics), [1]);
+    assert_eq!(count(&diagnostics, "Position"), 1);
+    assert_eq!(count(&diagnostics, "Velocity"), 0);
+    assert!(diagnostics.to_string().starts_with("1 entities in "));
+}
+
+#[test]
+fn entities_are_dumped_with_every_component() {
+    let mut world = World::new();
+    world.register_type::<Position>();
+    let entity = world.spawn((Position { x: 1.0, y: 2.0 }, Velocity(3.0)));
+    world.add_component(&entity, Selected);
+
+    let dump = format!("{:?}", world.debug_entity(&entity).unwrap());
+    assert!(dump.contains("Position"), "{dump}");
+    assert!(dump.contains("Velocity"), "{dump}");
+    assert!(dump.contains("Selected"), "{dump}");
+    // Only reflected components show their value.
+    assert!(dump.contains(r#"{"x":1.0,"y":2.0}"#), "{dump}");
+    assert!(dump.contains(".."), "{dump}");
+
+    world.despawn_entity(entity.clone());
+    assert!(world.debug_entity(&entity).is_none());
+}
+
+#[test]
+fn schedules_report_the_entities_each_system_ran_for() {
+    let mut world = World::new();
+    world.spawn((Position { x: 0.0, y: 0.0 }, Velocity(1.0)));
+    world.spawn(Position { x: 0.0, y: 0.0 });
+    world.insert_resource(Frames::default());
+
+    let mut schedule = Schedule::new();
+    schedule.add_system(
+        "move",
+        Stage::Update,
+        QuerySystem::<(&mut Position, &Velocity), _>::new(|_, (mut pos, vel)| pos.x += vel.0),
+    );
+    schedule.add_system(
+        "idle",
+        Stage::Update,
+        ResourceSystem::<ResMut<Frames>, _>::new(|frames| frames.0 += 1),
+    );
+    schedule.run(&mut world);
+
+    let systems = schedule.diagnostics();
+    let matched: Vec<_> = systems
+        .iter()
+        .map(|system| (system.name, system.matched_entities))
+        .collect();
+    assert!(matched.contains(&("move", Some(1))), "{matched:?}");
+    assert!(matched.contains(&("idle", None)), "{matched:?}");
+}
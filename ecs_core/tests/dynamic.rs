@@ -0,0 +1,76 @@
+use ecs_core::{
+    Component, DynamicLayout, DynamicQuery, DynamicValue, EcsError, StorageType, World,
+};
+
+#[derive(Component, Debug, PartialEq)]
+struct Speed(u32);
+
+fn schema() -> DynamicLayout {
+    DynamicLayout::Schema(ron::from_str("(hp: 0, tags: [\"\"])").unwrap())
+}
+
+fn value(ron: &str) -> DynamicValue {
+    DynamicValue::Value(ron::from_str(ron).unwrap())
+}
+
+#[test]
+fn values_must_match_the_registered_layout() {
+    let mut world = World::new();
+    let bytes =
+        world.register_dynamic_component("bytes", DynamicLayout::Bytes(2), StorageType::Table);
+    let stats = world.register_dynamic_component("stats", schema(), StorageType::SparseSet);
+    assert_eq!(world.dynamic_component_id("stats"), Some(stats));
+    let entity = world.spawn_entity();
+
+    world.insert_dynamic(&entity, bytes, DynamicValue::Bytes(vec![1, 2]));
+    world.insert_dynamic(&entity, stats, value("(hp: 3, tags: [\"a\", \"b\"])"));
+    assert_eq!(
+        world.try_insert_dynamic(&entity, bytes, DynamicValue::Bytes(vec![1])),
+        Err(EcsError::DynamicLayoutMismatch("bytes"))
+    );
+    assert_eq!(
+        world.try_insert_dynamic(&entity, stats, value("(hp: 3)")),
+        Err(EcsError::DynamicLayoutMismatch("stats"))
+    );
+
+    let speed = world.component_id::<Speed>();
+    assert_eq!(
+        world.try_insert_dynamic(&entity, speed, DynamicValue::Bytes(vec![])),
+        Err(EcsError::NotDynamic(speed))
+    );
+
+    assert_eq!(
+        world.remove_dynamic(&entity, bytes),
+        Some(DynamicValue::Bytes(vec![1, 2]))
+    );
+    assert_eq!(world.get_dynamic(&entity, bytes), None);
+    assert_eq!(
+        world.get_dynamic(&entity, stats),
+        Some(&value("(hp: 3, tags: [\"a\", \"b\"])"))
+    );
+}
+
+#[test]
+fn dynamic_queries_mix_rust_and_dynamic_components() {
+    let mut world = World::new();
+    let bytes =
+        world.register_dynamic_component("bytes", DynamicLayout::Bytes(1), StorageType::Table);
+    let speed = world.component_id::<Speed>();
+    let both = world.spawn(Speed(2));
+    world.insert_dynamic(&both, bytes, DynamicValue::Bytes(vec![1]));
+    world.spawn(Speed(3));
+
+    let mut query = DynamicQuery::new().read(speed).write(bytes);
+    let mut visited = vec![];
+    world.dynamic_system(&mut query, |entity, mut item| {
+        let speed = item.get::<Speed>(0).unwrap().0 as u8;
+        item.dynamic_mut(1).unwrap().as_bytes_mut().unwrap()[0] += speed;
+        visited.push(entity);
+    });
+
+    assert_eq!(visited, std::slice::from_ref(&both));
+    assert_eq!(
+        world.get_dynamic(&both, bytes),
+        Some(&DynamicValue::Bytes(vec![3]))
+    );
+}
@@ -0,0 +1,113 @@
+use ecs_core::{
+    Component, Event, EventReader, EventWriter, Res, ResMut, Resource, ResourceSystem, Schedule,
+    Stage, World,
+};
+
+struct A;
+impl Component for A {}
+
+#[derive(Debug, PartialEq)]
+struct Hit(u32);
+impl Event for Hit {}
+
+#[derive(Default)]
+struct Seen(Vec<u32>);
+impl Resource for Seen {}
+
+#[derive(Default)]
+struct Frame(u32);
+impl Resource for Frame {}
+
+fn world(entities: usize) -> World {
+    let mut world = World::new();
+    world.add_event::<Hit>();
+    for _ in 0..entities {
+        let entity = world.spawn_entity();
+        world.add_component(&entity, A);
+    }
+    world
+}
+
+fn read(world: &mut World) -> Vec<u32> {
+    let mut seen = vec![];
+    world.resource_system::<EventReader<Hit>, _>(|reader| {
+        seen.extend(reader.iter().map(|hit| hit.0));
+    });
+    seen
+}
+
+/// Reads events once for every entity with an `A`.
+fn read_per_entity(world: &mut World) -> usize {
+    let mut seen = 0;
+    world.system::<(&A, EventReader<Hit>), _>(|_, (_, reader)| seen += reader.len());
+    seen
+}
+
+#[test]
+fn an_event_is_seen_exactly_once() {
+    let mut world = world(3);
+    world.send_event(Hit(1));
+
+    assert_eq!(read(&mut world), vec![1]);
+    assert_eq!(read(&mut world), vec![]);
+}
+
+#[test]
+fn every_reader_sees_each_event() {
+    let mut world = world(3);
+    world.send_event(Hit(1));
+
+    let mut first = 0;
+    world.resource_system::<EventReader<Hit>, _>(|reader| first += reader.len());
+    let mut second = 0;
+    world.resource_system::<EventReader<Hit>, _>(|reader| second += reader.len());
+    assert_eq!((first, second), (1, 1));
+}
+
+#[test]
+fn events_are_read_in_the_next_frame() {
+    let mut world = world(0);
+    world.insert_resource(Seen::default());
+    world.insert_resource(Frame::default());
+
+    // The reader runs before the writer, so it only sees the event in the next frame.
+    let mut schedule = Schedule::new();
+    schedule.add_system(
+        "read",
+        Stage::Update,
+        ResourceSystem::<(EventReader<Hit>, ResMut<Seen>), _>::new(|(reader, seen)| {
+            seen.0.extend(reader.iter().map(|hit| hit.0))
+        }),
+    );
+    schedule.add_system(
+        "write",
+        Stage::PostUpdate,
+        ResourceSystem::<(EventWriter<Hit>, Res<Frame>), _>::new(|(mut writer, frame)| {
+            if frame.0 == 0 {
+                writer.send(Hit(7));
+            }
+        }),
+    );
+
+    let mut seen_per_frame = vec![];
+    for _ in 0..3 {
+        schedule.run(&mut world);
+        world.update_events();
+        world.resource_mut::<Frame>().unwrap().0 += 1;
+        seen_per_frame.push(std::mem::take(&mut world.resource_mut::<Seen>().unwrap().0));
+    }
+    assert_eq!(seen_per_frame, vec![vec![], vec![7], vec![]]);
+}
+
+#[test]
+fn the_cursor_only_moves_when_the_reader_is_fetched() {
+    let mut world = world(0);
+    world.send_event(Hit(1));
+
+    // No entity matches, so the event is not read yet.
+    assert_eq!(read_per_entity(&mut world), 0);
+    let entity = world.spawn_entity();
+    world.add_component(&entity, A);
+    assert_eq!(read_per_entity(&mut world), 1);
+    assert_eq!(read_per_entity(&mut world), 0);
+}
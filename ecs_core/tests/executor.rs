@@ -0,0 +1,158 @@
+use ecs_core::{
+    Changed, Commands, Component, Entity, ExclusiveSystem, ExecutorKind, QuerySystem, Res, ResMut,
+    Resource, ResourceSystem, Schedule, Stage, World,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Position(i64);
+impl Component for Position {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Velocity(i64);
+impl Component for Velocity {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Health(i64);
+impl Component for Health {}
+
+#[derive(Default)]
+struct Frame(i64);
+impl Resource for Frame {}
+
+/// Written by systems in the order they run, so any reordering shows up.
+#[derive(Default)]
+struct Log(Vec<(&'static str, i64)>);
+impl Resource for Log {}
+
+fn world() -> World {
+    let mut world = World::new();
+    world.insert_resource(Frame::default());
+    world.insert_resource(Log::default());
+    for i in 0..300 {
+        let entity = world.spawn_entity();
+        world.add_component(&entity, Position(i));
+        match i % 3 {
+            0 => world.add_component(&entity, Velocity(i % 5 - 2)),
+            1 => world.add_component(&entity, Health(i)),
+            _ => {
+                world.add_component(&entity, Velocity(1));
+                world.add_component(&entity, Health(100 - i));
+            }
+        }
+    }
+    world
+}
+
+fn schedule(executor: ExecutorKind) -> Schedule {
+    let mut schedule = Schedule::new();
+    schedule.set_executor(executor);
+
+    // Added first, but has to run after `heal`.
+    schedule
+        .add_system(
+            "record_health",
+            Stage::Update,
+            QuerySystem::<(&Health, ResMut<Log>), _>::new(|_, (health, log)| {
+                log.0.push(("health", health.0))
+            }),
+        )
+        .after("heal");
+    schedule.add_system(
+        "movement",
+        Stage::Update,
+        QuerySystem::<(&mut Position, &Velocity), _>::new(|_, (mut pos, vel)| pos.0 += vel.0),
+    );
+    // Conflicts with `movement`, so the two always run in the order they were added.
+    schedule.add_system(
+        "accelerate",
+        Stage::Update,
+        QuerySystem::<(&mut Velocity, Res<Frame>), _>::new(|_, (mut vel, frame)| {
+            vel.0 += frame.0 % 3 - 1
+        }),
+    );
+    // Does not conflict with either of the above.
+    schedule.add_system(
+        "heal",
+        Stage::Update,
+        QuerySystem::<&mut Health, _>::new(|_, mut health| {
+            if health.0 < 50 {
+                health.0 += 3;
+            }
+        }),
+    );
+    schedule.add_system(
+        "record_moved",
+        Stage::Update,
+        QuerySystem::<(&Position, Changed<Position>, ResMut<Log>), _>::new(|_, (pos, _, log)| {
+            log.0.push(("moved", pos.0))
+        }),
+    );
+    schedule.add_system(
+        "spawn",
+        Stage::PostUpdate,
+        ResourceSystem::<(Res<Frame>, Commands), _>::new(|(frame, mut commands)| {
+            commands
+                .spawn()
+                .insert(Position(frame.0))
+                .insert(Velocity(1));
+        }),
+    );
+    // Splits the run into two parallel batches.
+    schedule.add_system(
+        "despawn_stopped",
+        Stage::PostUpdate,
+        ExclusiveSystem::new(|world: &mut World| {
+            let mut stopped = vec![];
+            world.system::<&Velocity, _>(|entity, vel| {
+                if vel.0 == 0 {
+                    stopped.push(entity);
+                }
+            });
+            for entity in stopped {
+                world.despawn_entity(entity);
+            }
+        }),
+    );
+    schedule.add_system(
+        "advance_frame",
+        Stage::PostUpdate,
+        ResourceSystem::<ResMut<Frame>, _>::new(|frame| frame.0 += 1),
+    );
+    schedule
+}
+
+type State = (
+    Vec<(Entity, i64, Option<i64>, Option<i64>)>,
+    Vec<(&'static str, i64)>,
+);
+
+fn state(world: &mut World) -> State {
+    let mut entities = vec![];
+    world.system::<(&Position, Option<&Velocity>, Option<&Health>), _>(
+        |entity, (pos, vel, health)| {
+            entities.push((entity, pos.0, vel.map(|v| v.0), health.map(|h| h.0)))
+        },
+    );
+    entities.sort_by_key(|(entity, ..)| entity.id());
+    let log = std::mem::take(&mut world.resource_mut::<Log>().unwrap().0);
+    (entities, log)
+}
+
+#[test]
+fn parallel_runs_match_sequential_runs() {
+    let mut sequential = world();
+    let mut parallel = world();
+    let mut sequential_schedule = schedule(ExecutorKind::SingleThreaded);
+    let mut parallel_schedule = schedule(ExecutorKind::MultiThreaded);
+
+    for frame in 0..20 {
+        sequential_schedule.run(&mut sequential);
+        parallel_schedule.run(&mut parallel);
+        assert_eq!(
+            state(&mut sequential),
+            state(&mut parallel),
+            "frame {frame}"
+        );
+    }
+    assert_eq!(sequential.change_tick(), parallel.change_tick());
+}
@@ -0,0 +1,97 @@
+use ecs_core::{Children, Component, EcsError, Entity, Parent, World};
+
+#[derive(Component)]
+struct A;
+
+fn children(world: &World, entity: &Entity) -> Vec<Entity> {
+    world
+        .get_component::<Children>(entity)
+        .map(|children| children.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+#[test]
+fn parents_and_children_stay_in_sync() {
+    let mut world = World::new();
+    let [first, second, child] = [(); 3].map(|_| world.spawn(A));
+
+    world.set_parent(&child, &first);
+    assert_eq!(children(&world, &first), vec![child.clone()]);
+
+    world.set_parent(&child, &second);
+    assert_eq!(children(&world, &first), []);
+    assert_eq!(children(&world, &second), vec![child.clone()]);
+    assert_eq!(
+        world.get_component::<Parent>(&child).map(Parent::get),
+        Some(&second)
+    );
+
+    assert_eq!(world.remove_parent(&child), Some(second.clone()));
+    assert!(world.get_component::<Children>(&second).is_none());
+}
+
+#[test]
+fn cycles_are_rejected() {
+    let mut world = World::new();
+    let [root, child] = [(); 2].map(|_| world.spawn(A));
+    world.set_parent(&child, &root);
+
+    assert_eq!(
+        world.try_set_parent(&root, &child),
+        Err(EcsError::HierarchyCycle {
+            child: root.clone(),
+            parent: child.clone(),
+        })
+    );
+    assert!(world.try_set_parent(&root, &root).is_err());
+}
+
+#[test]
+fn despawning_detaches_from_the_hierarchy() {
+    let mut world = World::new();
+    let [root, middle, leaf] = [(); 3].map(|_| world.spawn(A));
+    world.set_parent(&middle, &root);
+    world.set_parent(&leaf, &middle);
+
+    world.despawn_entity(middle);
+    assert!(world.get_component::<Children>(&root).is_none());
+    assert!(world.get_component::<Parent>(&leaf).is_none());
+}
+
+#[test]
+fn despawn_recursive_removes_descendants() {
+    let mut world = World::new();
+    let [root, middle, leaf, other] = [(); 4].map(|_| world.spawn(A));
+    world.set_parent(&middle, &root);
+    world.set_parent(&leaf, &middle);
+    world.set_parent(&root, &other);
+
+    world.despawn_recursive(root.clone());
+    for entity in [&root, &middle, &leaf] {
+        assert!(!world.is_alive(entity));
+    }
+    assert!(world.is_alive(&other));
+    assert!(world.get_component::<Children>(&other).is_none());
+
+    assert_eq!(
+        world.try_despawn_recursive(root.clone()),
+        Err(EcsError::EntityNotFound(root))
+    );
+}
+
+#[test]
+fn despawn_recursive_skips_children_that_are_already_gone() {
+    let mut world = World::new();
+    let [root, child, copy] = [(); 3].map(|_| world.spawn(A));
+    world.set_parent(&child, &root);
+
+    // `copy` lists the same child without being its parent, so the child can be despawned
+    // without `copy` noticing.
+    let listed = world.get_component::<Children>(&root).unwrap().clone();
+    world.insert_component(&copy, listed);
+    world.despawn_recursive(root);
+    assert!(!world.is_alive(&child));
+
+    assert_eq!(world.try_despawn_recursive(copy.clone()), Ok(()));
+    assert!(!world.is_alive(&copy));
+}
@@ -0,0 +1,103 @@
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use ecs_core::{Component, Entity, HookKind, World};
+
+#[derive(Component, Debug, PartialEq)]
+struct A(u32);
+
+#[derive(Component, Debug, PartialEq)]
+struct B;
+
+fn counter() -> (Arc<AtomicUsize>, Arc<AtomicUsize>) {
+    let count = Arc::new(AtomicUsize::new(0));
+    (count.clone(), count)
+}
+
+#[test]
+fn removing_a_component_from_its_own_hook_does_not_recurse() {
+    let mut world = World::new();
+    let (count, hook_count) = counter();
+    world.on_remove::<A>(move |world, entity| {
+        hook_count.fetch_add(1, Ordering::Relaxed);
+        assert_eq!(world.get_component::<A>(entity), Some(&A(1)));
+        assert_eq!(world.remove_component::<A>(entity), None);
+    });
+
+    let entity = world.spawn(A(1));
+    assert_eq!(world.remove_component::<A>(&entity), Some(A(1)));
+    assert_eq!(world.get_component::<A>(&entity), None);
+    assert_eq!(count.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn despawning_from_a_remove_hook_still_returns_the_component() {
+    let mut world = World::new();
+    world.on_remove::<A>(|world, entity| {
+        world.despawn_entity(entity.clone());
+    });
+    let (count, hook_count) = counter();
+    world.on_remove::<B>(move |_, _| {
+        hook_count.fetch_add(1, Ordering::Relaxed);
+    });
+
+    let entity = world.spawn((A(1), B));
+    assert_eq!(world.remove_component::<A>(&entity), Some(A(1)));
+    assert!(!world.is_alive(&entity));
+    assert_eq!(count.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn hooks_run_in_lifecycle_order() {
+    let mut world = World::new();
+    let log = Arc::new(Mutex::new(vec![]));
+    for (kind, name) in [
+        (HookKind::Add, "add"),
+        (HookKind::Insert, "insert"),
+        (HookKind::Remove, "remove"),
+    ] {
+        let log = log.clone();
+        world.add_hook::<A>(
+            kind,
+            Arc::new(move |world: &mut World, entity: &Entity| {
+                let value = world.get_component::<A>(entity).map(|a| a.0);
+                log.lock().unwrap().push((name, value));
+            }),
+        );
+    }
+
+    let entity = world.spawn(A(1));
+    world.insert_component(&entity, A(2));
+    world.despawn_entity(entity);
+    assert_eq!(
+        *log.lock().unwrap(),
+        [
+            ("add", Some(1)),
+            ("insert", Some(1)),
+            ("insert", Some(2)),
+            ("remove", Some(2)),
+        ]
+    );
+}
+
+#[test]
+fn components_removed_while_despawning_run_their_hooks_once() {
+    let mut world = World::new();
+    world.on_remove::<A>(|world, entity| {
+        assert_eq!(world.remove_component::<B>(entity), Some(B));
+    });
+    let (count, hook_count) = counter();
+    world.on_remove::<B>(move |world, entity| {
+        hook_count.fetch_add(1, Ordering::Relaxed);
+        assert_eq!(world.get_component::<B>(entity), Some(&B));
+    });
+
+    // `A` was registered first, so its hook runs first and removes `B` before the despawn gets
+    // to it.
+    let entity = world.spawn((A(1), B));
+    world.despawn_entity(entity.clone());
+    assert!(!world.is_alive(&entity));
+    assert_eq!(count.load(Ordering::Relaxed), 1);
+}
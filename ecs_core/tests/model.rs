@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+
+use ecs_core::{Component, EcsError, Entity, World};
+use proptest::{prelude::*, sample::Index};
+
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+struct A(u32);
+
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+struct B(u64);
+
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+#[component(storage = "SparseSet")]
+struct S(i32);
+
+/// The components an entity should have, according to the model.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Expected {
+    a: Option<A>,
+    b: Option<B>,
+    s: Option<S>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Value {
+    A(A),
+    B(B),
+    S(S),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    A,
+    B,
+    S,
+}
+
+/// Operations on entities are applied to a handle picked from every entity spawned so far,
+/// including despawned ones, so stale handles are exercised too.
+#[derive(Debug, Clone)]
+enum Op {
+    Spawn(Expected),
+    Add(Index, Value),
+    Insert(Index, Value),
+    Remove(Index, Kind),
+    Despawn(Index),
+}
+
+fn expected() -> impl Strategy<Value = Expected> {
+    (
+        proptest::option::of(any::<u32>().prop_map(A)),
+        proptest::option::of(any::<u64>().prop_map(B)),
+        proptest::option::of(any::<i32>().prop_map(S)),
+    )
+        .prop_map(|(a, b, s)| Expected { a, b, s })
+}
+
+fn value() -> impl Strategy<Value = Value> {
+    prop_oneof![
+        any::<u32>().prop_map(|v| Value::A(A(v))),
+        any::<u64>().prop_map(|v| Value::B(B(v))),
+        any::<i32>().prop_map(|v| Value::S(S(v))),
+    ]
+}
+
+fn kind() -> impl Strategy<Value = Kind> {
+    prop_oneof![Just(Kind::A), Just(Kind::B), Just(Kind::S)]
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        2 => expected().prop_map(Op::Spawn),
+        2 => (any::<Index>(), value()).prop_map(|(i, v)| Op::Add(i, v)),
+        1 => (any::<Index>(), value()).prop_map(|(i, v)| Op::Insert(i, v)),
+        2 => (any::<Index>(), kind()).prop_map(|(i, k)| Op::Remove(i, k)),
+        2 => any::<Index>().prop_map(Op::Despawn),
+    ]
+}
+
+fn spawn(world: &mut World, expected: Expected) -> Entity {
+    match (expected.a, expected.b, expected.s) {
+        (None, None, None) => world.spawn_entity(),
+        (Some(a), None, None) => world.spawn(a),
+        (None, Some(b), None) => world.spawn(b),
+        (None, None, Some(s)) => world.spawn(s),
+        (Some(a), Some(b), None) => world.spawn((a, b)),
+        (Some(a), None, Some(s)) => world.spawn((a, s)),
+        (None, Some(b), Some(s)) => world.spawn((b, s)),
+        (Some(a), Some(b), Some(s)) => world.spawn((a, b, s)),
+    }
+}
+
+impl Expected {
+    fn set(&mut self, value: Value) {
+        match value {
+            Value::A(a) => self.a = Some(a),
+            Value::B(b) => self.b = Some(b),
+            Value::S(s) => self.s = Some(s),
+        }
+    }
+
+    fn has(&self, value: Value) -> bool {
+        match value {
+            Value::A(_) => self.a.is_some(),
+            Value::B(_) => self.b.is_some(),
+            Value::S(_) => self.s.is_some(),
+        }
+    }
+}
+
+fn add(world: &mut World, entity: &Entity, value: Value) -> Result<(), EcsError> {
+    match value {
+        Value::A(a) => world.try_add_component(entity, a),
+        Value::B(b) => world.try_add_component(entity, b),
+        Value::S(s) => world.try_add_component(entity, s),
+    }
+}
+
+fn insert(world: &mut World, entity: &Entity, value: Value) -> Option<Value> {
+    match value {
+        Value::A(a) => world.insert_component(entity, a).map(Value::A),
+        Value::B(b) => world.insert_component(entity, b).map(Value::B),
+        Value::S(s) => world.insert_component(entity, s).map(Value::S),
+    }
+}
+
+/// Removes the component from both the world and the model, checking they agree on its value.
+fn remove(world: &mut World, entity: &Entity, expected: &mut Expected, kind: Kind) {
+    match kind {
+        Kind::A => assert_eq!(world.remove_component::<A>(entity), expected.a.take()),
+        Kind::B => assert_eq!(world.remove_component::<B>(entity), expected.b.take()),
+        Kind::S => assert_eq!(world.remove_component::<S>(entity), expected.s.take()),
+    }
+}
+
+/// Checks every handle ever spawned against the model, both through direct reads and queries.
+fn check(world: &mut World, handles: &[Entity], model: &HashMap<Entity, Expected>) {
+    for entity in handles {
+        let expected = model.get(entity);
+        assert_eq!(world.is_alive(entity), expected.is_some(), "{entity:?}");
+        let expected = expected.copied().unwrap_or_default();
+        assert_eq!(world.get_component::<A>(entity), expected.a.as_ref());
+        assert_eq!(world.get_component::<B>(entity), expected.b.as_ref());
+        assert_eq!(world.get_component::<S>(entity), expected.s.as_ref());
+    }
+
+    let mut queried: HashMap<Entity, Expected> = HashMap::new();
+    world.system::<(Option<&A>, Option<&B>, Option<&S>), _>(|entity, (a, b, s)| {
+        queried.insert(
+            entity,
+            Expected {
+                a: a.copied(),
+                b: b.copied(),
+                s: s.copied(),
+            },
+        );
+    });
+    assert_eq!(&queried, model);
+
+    let mut with_a_and_s = 0;
+    world.system::<(&A, &S), _>(|entity, (a, s)| {
+        assert_eq!(model[&entity].a, Some(*a));
+        assert_eq!(model[&entity].s, Some(*s));
+        with_a_and_s += 1;
+    });
+    assert_eq!(
+        with_a_and_s,
+        model
+            .values()
+            .filter(|e| e.a.is_some() && e.s.is_some())
+            .count()
+    );
+    assert_eq!(world.diagnostics().entity_count, model.len());
+}
+
+proptest! {
+    #[test]
+    fn world_matches_model(ops in proptest::collection::vec(op(), 1..200)) {
+        let mut world = World::new();
+        let mut handles: Vec<Entity> = vec![];
+        let mut model: HashMap<Entity, Expected> = HashMap::new();
+
+        for op in ops {
+            match op {
+                Op::Spawn(expected) => {
+                    let entity = spawn(&mut world, expected);
+                    prop_assert!(!handles.contains(&entity), "handle reused: {entity:?}");
+                    handles.push(entity.clone());
+                    model.insert(entity, expected);
+                }
+                Op::Add(index, value) if !handles.is_empty() => {
+                    let entity = index.get(&handles).clone();
+                    let result = add(&mut world, &entity, value);
+                    match model.get_mut(&entity) {
+                        None => prop_assert_eq!(result, Err(EcsError::EntityNotFound(entity))),
+                        Some(expected) if expected.has(value) => {
+                            let exists = matches!(result, Err(EcsError::ComponentAlreadyExists { .. }));
+                            prop_assert!(exists, "{:?}", result);
+                        }
+                        Some(expected) => {
+                            prop_assert_eq!(result, Ok(()));
+                            expected.set(value);
+                        }
+                    }
+                }
+                Op::Insert(index, value) => {
+                    // Inserting into a despawned entity panics, so only live ones are used.
+                    let live: Vec<Entity> =
+                        handles.iter().filter(|e| model.contains_key(e)).cloned().collect();
+                    if !live.is_empty() {
+                        let entity = index.get(&live);
+                        let expected = model.get_mut(entity).unwrap();
+                        let had = expected.has(value);
+                        let replaced = insert(&mut world, entity, value);
+                        prop_assert_eq!(replaced.is_some(), had);
+                        expected.set(value);
+                    }
+                }
+                Op::Remove(index, kind) if !handles.is_empty() => {
+                    let entity = index.get(&handles).clone();
+                    let mut none = Expected::default();
+                    let expected = model.get_mut(&entity).unwrap_or(&mut none);
+                    remove(&mut world, &entity, expected, kind);
+                }
+                Op::Despawn(index) if !handles.is_empty() => {
+                    let entity = index.get(&handles).clone();
+                    let result = world.try_despawn(entity.clone());
+                    match model.remove(&entity) {
+                        Some(_) => prop_assert_eq!(result, Ok(())),
+                        None => prop_assert_eq!(result, Err(EcsError::EntityNotFound(entity))),
+                    }
+                }
+                _ => {}
+            }
+            check(&mut world, &handles, &model);
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use ecs_core::{Component, With, Without, World};
+
+struct Position(i32);
+impl Component for Position {}
+
+struct Velocity(i32);
+impl Component for Velocity {}
+
+#[test]
+fn systems_only_visit_matching_entities() {
+    let mut world = World::new();
+    let moving = world.spawn_entity();
+    world.add_component(&moving, Position(0));
+    world.add_component(&moving, Velocity(2));
+    let still = world.spawn_entity();
+    world.add_component(&still, Position(5));
+
+    let mut visited = Vec::new();
+    world.system::<(&mut Position, &Velocity), _>(|entity, (mut position, velocity)| {
+        position.0 += velocity.0;
+        visited.push(entity);
+    });
+
+    assert_eq!(visited, vec![moving.clone()]);
+    assert_eq!(world.get_component_mut::<Position>(&moving).unwrap().0, 2);
+    assert_eq!(world.get_component_mut::<Position>(&still).unwrap().0, 5);
+}
+
+#[test]
+fn archetypes_created_after_a_run_are_matched() {
+    let mut world = World::new();
+    let first = world.spawn_entity();
+    world.add_component(&first, Position(1));
+
+    let mut count = 0;
+    world.system::<&Position, _>(|_, _| count += 1);
+    assert_eq!(count, 1);
+
+    // Adding the components in another order still maps to the same archetype.
+    let second = world.spawn_entity();
+    world.add_component(&second, Velocity(0));
+    world.add_component(&second, Position(2));
+    let third = world.spawn_entity();
+    world.add_component(&third, Position(3));
+    world.add_component(&third, Velocity(0));
+
+    let mut sum = 0;
+    world.system::<&Position, _>(|_, position| sum += position.0);
+    assert_eq!(sum, 6);
+
+    let mut count = 0;
+    world.system::<(&Velocity, &Position), _>(|_, _| count += 1);
+    assert_eq!(count, 2);
+}
+
+#[test]
+fn filters_match_without_fetching() {
+    let mut world = World::new();
+    let moving = world.spawn_entity();
+    world.add_component(&moving, Position(0));
+    world.add_component(&moving, Velocity(1));
+    let still = world.spawn_entity();
+    world.add_component(&still, Position(0));
+
+    let mut with = Vec::new();
+    world.system::<With<Velocity>, _>(|entity, _| with.push(entity));
+    let mut without = Vec::new();
+    world.system::<(&Position, Without<Velocity>), _>(|entity, _| without.push(entity));
+
+    assert_eq!(with, vec![moving]);
+    assert_eq!(without, vec![still]);
+}
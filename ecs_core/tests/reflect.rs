@@ -0,0 +1,146 @@
+use ecs_core::{Changed, Component, EcsError, Reflect, ReflectValue, World};
+use serde::{Deserialize, Serialize};
+
+#[derive(Component, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[component(reflect)]
+struct Position {
+    x: f32,
+    y: f32,
+}
+
+#[derive(Component, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[component(reflect)]
+struct Name(String);
+
+/// Counts the `Position`s changed since the last call.
+fn changed_positions(world: &mut World) -> usize {
+    let mut changed = 0;
+    world.system::<Changed<Position>, _>(|_, _| changed += 1);
+    changed
+}
+
+fn world() -> World {
+    let mut world = World::new();
+    world.register_type::<Position>();
+    world.register_type::<Name>();
+    world
+}
+
+#[test]
+fn derived_fields_are_named() {
+    assert_eq!(Position::field_names(), &["x", "y"]);
+    assert_eq!(Name::field_names(), &["0"]);
+
+    let world = world();
+    let registration = world.type_registry().get("Position").unwrap();
+    assert_eq!(registration.field_names(), &["x", "y"]);
+}
+
+#[test]
+fn fields_are_read_and_written_by_name() {
+    let mut world = world();
+    let entity = world.spawn((Position { x: 1.0, y: 2.0 }, Name("crate".to_string())));
+
+    assert_eq!(
+        world.get_field(&entity, "Position", "y"),
+        Ok(ReflectValue::from(2.0f32))
+    );
+    world.set_field(&entity, "Position", "x", 5.0).unwrap();
+    world.set_field(&entity, "Name", "0", "box").unwrap();
+
+    assert_eq!(
+        world.get_component::<Position>(&entity),
+        Some(&Position { x: 5.0, y: 2.0 })
+    );
+    assert_eq!(
+        world.get_component::<Name>(&entity),
+        Some(&Name("box".to_string()))
+    );
+
+    assert_eq!(changed_positions(&mut world), 1);
+}
+
+#[test]
+fn invalid_fields_are_rejected() {
+    let mut world = world();
+    let entity = world.spawn(Position { x: 1.0, y: 2.0 });
+
+    assert!(matches!(
+        world.get_field(&entity, "Position", "z"),
+        Err(EcsError::UnknownField { .. })
+    ));
+    assert!(matches!(
+        world.set_field(&entity, "Position", "x", "left"),
+        Err(EcsError::Reflect(_))
+    ));
+    assert!(matches!(
+        world.get_field(&entity, "Name", "0"),
+        Err(EcsError::MissingComponent { .. })
+    ));
+    assert_eq!(
+        world.get_component::<Position>(&entity),
+        Some(&Position { x: 1.0, y: 2.0 })
+    );
+}
+
+#[test]
+fn rejected_values_do_not_mark_the_component_as_changed() {
+    let mut world = world();
+    let entity = world.spawn(Position { x: 1.0, y: 2.0 });
+    assert_eq!(changed_positions(&mut world), 1);
+
+    assert!(world.set_field(&entity, "Position", "x", "left").is_err());
+    assert_eq!(changed_positions(&mut world), 0);
+
+    world.set_field(&entity, "Position", "x", 3.0).unwrap();
+    assert_eq!(changed_positions(&mut world), 1);
+}
+
+#[test]
+fn types_are_registered_under_one_name() {
+    let mut world = world();
+    world.register_type::<Position>();
+    assert_eq!(world.type_registry().len(), 2);
+
+    assert!(matches!(
+        world.try_register_type_named::<Position>("Location"),
+        Err(EcsError::TypeAlreadyRegistered {
+            name: "Position",
+            ..
+        })
+    ));
+    assert!(matches!(
+        world.try_register_type_named::<Name>("Position"),
+        Err(EcsError::TypeNameTaken {
+            name: "Position",
+            ..
+        })
+    ));
+    assert!(world.type_registry().get("Location").is_none());
+}
+
+#[test]
+fn components_are_inserted_by_name() {
+    let mut world = world();
+    let entity = world.spawn(Name("crate".to_string()));
+
+    let value = world.reflect_component(&entity, "Name").unwrap();
+    world
+        .insert_by_name(&entity, "Name", value.clone())
+        .unwrap();
+    assert!(matches!(
+        world.insert_by_name(&entity, "Position", value),
+        Err(EcsError::Reflect(_))
+    ));
+    assert!(matches!(
+        world.reflect_component(&entity, "Velocity"),
+        Err(EcsError::UnknownTypeName(_))
+    ));
+    assert_eq!(
+        world.reflect_entity(&entity),
+        Ok(vec![(
+            "Name",
+            ReflectValue::Seq(vec![ReflectValue::String("crate".to_string())])
+        )])
+    );
+}
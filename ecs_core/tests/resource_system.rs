@@ -0,0 +1,68 @@
+use ecs_core::{Commands, Component, Res, ResMut, Resource, World};
+
+struct A;
+impl Component for A {}
+
+struct DeltaTime(f32);
+impl Resource for DeltaTime {}
+
+#[derive(Default)]
+struct Elapsed(f32);
+impl Resource for Elapsed {}
+
+fn world(entities: usize) -> World {
+    let mut world = World::new();
+    world.insert_resource(DeltaTime(0.5));
+    world.insert_resource(Elapsed::default());
+    for _ in 0..entities {
+        let entity = world.spawn_entity();
+        world.add_component(&entity, A);
+    }
+    world
+}
+
+#[test]
+fn runs_once_regardless_of_entities() {
+    for entities in [0, 3] {
+        let mut world = world(entities);
+        let ran = world.resource_system::<(Res<DeltaTime>, ResMut<Elapsed>), _>(
+            |(delta_time, elapsed)| elapsed.0 += delta_time.0,
+        );
+        assert!(ran);
+        assert_eq!(world.resource::<Elapsed>().unwrap().0, 0.5);
+    }
+}
+
+#[test]
+fn does_not_run_without_its_resources() {
+    let mut world = World::new();
+    assert!(!world.resource_system::<Res<DeltaTime>, _>(|_| panic!("ran")));
+}
+
+#[test]
+#[should_panic(expected = "Resource systems cannot use components")]
+fn rejects_components() {
+    world(1).resource_system::<(&A, Res<DeltaTime>), _>(|_| {});
+}
+
+#[test]
+fn resources_are_shared_by_queries() {
+    let mut world = world(2);
+    world.system::<(&A, ResMut<Elapsed>), _>(|_, (_, elapsed)| elapsed.0 += 1.0);
+    assert_eq!(world.resource::<Elapsed>().unwrap().0, 2.0);
+
+    world.remove_resource::<Elapsed>();
+    world.system::<(&A, ResMut<Elapsed>), _>(|_, _| panic!("ran without its resource"));
+}
+
+#[test]
+fn commands_are_recorded_once() {
+    let mut world = world(3);
+    world.resource_system::<Commands, _>(|mut commands| {
+        commands.spawn().insert(A);
+    });
+
+    let mut count = 0;
+    world.system::<&A, _>(|_, _| count += 1);
+    assert_eq!(count, 4);
+}
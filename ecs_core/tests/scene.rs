@@ -0,0 +1,152 @@
+use ecs_core::{
+    Children, Component, EcsError, Entity, EntityMap, MapEntities, Parent, Scene, World,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Component, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[component(reflect)]
+struct Position {
+    x: f32,
+    y: f32,
+}
+
+/// Not registered, so it is left out of saved scenes.
+#[derive(Component)]
+struct Cache;
+
+#[derive(Component, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[component(reflect)]
+struct Target(Entity);
+
+impl MapEntities for Target {
+    fn map_entities(&mut self, entities: &EntityMap) -> Result<(), EcsError> {
+        entities.map_entity(&mut self.0)
+    }
+}
+
+fn world() -> World {
+    let mut world = World::new();
+    world.register_type::<Position>();
+    world.register_type_with_entities::<Target>();
+    world
+}
+
+fn entity_count(world: &World) -> usize {
+    world
+        .archetypes()
+        .iter()
+        .map(|archetype| archetype.entities().len())
+        .sum()
+}
+
+#[test]
+fn scenes_round_trip_through_ron() {
+    let mut world = world();
+    let root = world.spawn((Position { x: 1.0, y: 2.0 }, Cache));
+    let child = world.spawn(Position { x: 3.0, y: 4.0 });
+    world.set_parent(&child, &root);
+
+    let ron = world.save_scene().unwrap().to_ron().unwrap();
+    let scene = Scene::from_ron(&ron).unwrap();
+    assert_eq!(scene, world.save_scene().unwrap());
+
+    let mut loaded = self::world();
+    let entities = loaded.load_scene(&scene).unwrap();
+    let [root, child] = [&root, &child].map(|entity| entities.get(entity).unwrap().clone());
+
+    assert_eq!(
+        loaded.get_component::<Position>(&root),
+        Some(&Position { x: 1.0, y: 2.0 })
+    );
+    assert_eq!(
+        loaded.get_component::<Position>(&child),
+        Some(&Position { x: 3.0, y: 4.0 })
+    );
+    assert!(loaded.get_component::<Cache>(&root).is_none());
+    assert_eq!(
+        loaded.get_component::<Parent>(&child).map(Parent::get),
+        Some(&root)
+    );
+    assert_eq!(
+        loaded
+            .get_component::<Children>(&root)
+            .map(|children| children.iter().cloned().collect::<Vec<_>>()),
+        Some(vec![child])
+    );
+}
+
+#[test]
+fn entity_references_are_remapped() {
+    let mut world = world();
+    let target = world.spawn(Position { x: 1.0, y: 2.0 });
+    let follower = world.spawn(Target(target.clone()));
+    let scene = world.save_scene().unwrap();
+
+    // Entities already in the world take the ids the scene was saved with.
+    let mut loaded = self::world();
+    loaded.spawn(Position { x: 0.0, y: 0.0 });
+    loaded.spawn(Position { x: 0.0, y: 0.0 });
+    let entities = loaded.load_scene(&scene).unwrap();
+
+    let target = entities.get(&target).unwrap();
+    let follower = entities.get(&follower).unwrap();
+    assert_ne!(target, &scene.entities[0].entity);
+    assert_eq!(
+        loaded.get_component::<Target>(follower),
+        Some(&Target(target.clone()))
+    );
+}
+
+#[test]
+fn references_outside_the_scene_fail_the_load() {
+    let mut world = world();
+    // The entity id matches the scene entity, but the generation does not.
+    let scene = Scene::from_ron(
+        r#"(
+            entities: [
+                (entity: (id: 0, generation: 0), components: {
+                    "Target": ((id: 0, generation: 1)),
+                }),
+            ],
+        )"#,
+    )
+    .unwrap();
+
+    assert!(matches!(
+        world.load_scene(&scene),
+        Err(EcsError::UnknownSceneEntity(_))
+    ));
+    assert_eq!(entity_count(&world), 0);
+}
+
+#[test]
+fn unknown_components_fail_the_load() {
+    let mut world = world();
+    let scene = Scene::from_ron(
+        r#"(
+            entities: [
+                (entity: (id: 0, generation: 0), components: {
+                    "Position": (x: 1.0, y: 2.0),
+                }),
+                (entity: (id: 1, generation: 0), components: {
+                    "Velocity": (x: 1.0, y: 2.0),
+                }),
+            ],
+        )"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        world.load_scene(&scene).err(),
+        Some(EcsError::UnknownTypeName("Velocity".to_string()))
+    );
+    assert_eq!(entity_count(&world), 0);
+}
+
+#[test]
+fn malformed_ron_is_rejected() {
+    assert!(matches!(
+        Scene::from_ron("(entities: [(entity: 0,)]"),
+        Err(EcsError::SceneFormat(_))
+    ));
+}
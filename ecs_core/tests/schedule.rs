@@ -0,0 +1,129 @@
+use ecs_core::{
+    Changed, Component, EcsError, ExclusiveSystem, QuerySystem, ResMut, Resource, ResourceSystem,
+    Schedule, Stage, System, World,
+};
+
+struct A(u32);
+impl Component for A {}
+
+#[derive(Default)]
+struct Log(Vec<&'static str>);
+impl Resource for Log {}
+
+fn log(name: &'static str) -> impl System {
+    ResourceSystem::<ResMut<Log>, _>::new(move |log| log.0.push(name))
+}
+
+fn world() -> World {
+    let mut world = World::new();
+    world.insert_resource(Log::default());
+    for i in 0..3 {
+        let entity = world.spawn_entity();
+        world.add_component(&entity, A(i));
+    }
+    world
+}
+
+#[test]
+fn systems_run_by_stage_then_constraints() {
+    let mut schedule = Schedule::new();
+    schedule.add_system("render", Stage::RenderExtract, log("render"));
+    schedule.add_system("late", Stage::Update, log("late"));
+    schedule
+        .add_system("early", Stage::Update, log("early"))
+        .before("late");
+    schedule.add_system("input", Stage::PreUpdate, log("input"));
+
+    assert_eq!(
+        schedule.system_order(),
+        Ok(vec!["input", "early", "late", "render"])
+    );
+
+    let mut world = world();
+    schedule.run(&mut world);
+    assert_eq!(
+        world.resource::<Log>().unwrap().0,
+        ["input", "early", "late", "render"]
+    );
+}
+
+#[test]
+fn invalid_constraints_are_reported() {
+    let mut schedule = Schedule::new();
+    schedule.add_system("a", Stage::Update, log("a")).after("b");
+    schedule.add_system("b", Stage::Update, log("b")).after("a");
+    assert!(matches!(schedule.build(), Err(EcsError::SystemCycle(_))));
+
+    let mut schedule = Schedule::new();
+    schedule
+        .add_system("a", Stage::Update, log("a"))
+        .after("missing");
+    assert_eq!(
+        schedule.build(),
+        Err(EcsError::UnknownSystem {
+            system: "a",
+            dependency: "missing",
+        })
+    );
+
+    let mut schedule = Schedule::new();
+    schedule
+        .add_system("a", Stage::PostUpdate, log("a"))
+        .before("b");
+    schedule.add_system("b", Stage::Update, log("b"));
+    assert_eq!(
+        schedule.build(),
+        Err(EcsError::StageOrderConflict {
+            before: "a",
+            after: "b",
+        })
+    );
+}
+
+#[test]
+fn query_systems_track_changes_separately() {
+    let mut world = world();
+    let mut schedule = Schedule::new();
+    for name in ["first", "second"] {
+        schedule.add_system(
+            name,
+            Stage::PostUpdate,
+            QuerySystem::<(Changed<A>, ResMut<Log>), _>::new(move |_, (_, log)| log.0.push(name)),
+        );
+    }
+
+    schedule.run(&mut world);
+    assert_eq!(world.resource::<Log>().unwrap().0.len(), 6);
+
+    world.resource_mut::<Log>().unwrap().0.clear();
+    schedule.run(&mut world);
+    assert!(world.resource::<Log>().unwrap().0.is_empty());
+
+    // The first system seeing the change does not hide it from the second.
+    world.system::<&mut A, _>(|_, mut a| {
+        if a.0 == 0 {
+            a.0 = 10;
+        }
+    });
+    schedule.run(&mut world);
+    assert_eq!(world.resource::<Log>().unwrap().0, ["first", "second"]);
+}
+
+#[test]
+fn resource_systems_run_once_per_schedule_run() {
+    let mut world = world();
+    let mut schedule = Schedule::new();
+    schedule.add_system("log", Stage::Update, log("log"));
+    schedule.add_system(
+        "spawn",
+        Stage::PostUpdate,
+        ExclusiveSystem::new(|world: &mut World| {
+            let entity = world.spawn_entity();
+            world.add_component(&entity, A(0));
+        }),
+    );
+
+    schedule.run(&mut world);
+    schedule.run(&mut world);
+    assert_eq!(world.resource::<Log>().unwrap().0, ["log", "log"]);
+}
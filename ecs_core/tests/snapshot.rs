@@ -0,0 +1,141 @@
+use ecs_core::{Component, EcsError, Entity, Without, World};
+
+#[derive(Component, Debug, Clone, PartialEq)]
+struct Position {
+    x: f32,
+    y: f32,
+}
+
+#[derive(Component, Debug, Clone, PartialEq)]
+struct Velocity {
+    x: f32,
+    y: f32,
+}
+
+#[derive(Component, Debug, Clone, PartialEq)]
+#[component(storage = "SparseSet")]
+struct Frozen;
+
+fn world() -> World {
+    let mut world = World::new();
+    world.register_snapshot::<Position>();
+    world.register_snapshot::<Velocity>();
+    world.register_snapshot::<Frozen>();
+    world
+}
+
+/// Moves every unfrozen entity, then despawns those that left the area and spawns a new one, so
+/// a step changes component values, archetypes and the entity registry.
+fn step(world: &mut World, frame: u32) {
+    world.system::<(&mut Position, &Velocity, Without<Frozen>), _>(|_, (mut pos, vel, _)| {
+        pos.x += vel.x;
+        pos.y += vel.y;
+    });
+
+    let mut outside = vec![];
+    world.system::<&Position, _>(|entity, pos| {
+        if pos.x.abs() > 4.0 {
+            outside.push(entity);
+        }
+    });
+    for entity in outside {
+        world.despawn_entity(entity);
+    }
+
+    let entity = world.spawn((
+        Position { x: 0.0, y: 0.0 },
+        Velocity {
+            x: frame as f32 - 1.5,
+            y: 1.0,
+        },
+    ));
+    if frame.is_multiple_of(2) {
+        world.add_component(&entity, Frozen);
+    }
+}
+
+fn positions(world: &mut World) -> Vec<(Entity, Position)> {
+    let mut positions = vec![];
+    world.system::<&Position, _>(|entity, pos| positions.push((entity, pos.clone())));
+    positions.sort_by_key(|(entity, _)| entity.id());
+    positions
+}
+
+#[test]
+fn restore_then_replay_reaches_the_same_state() {
+    let mut world = world();
+    for frame in 0..4 {
+        step(&mut world, frame);
+    }
+
+    let start = world.snapshot().unwrap();
+    let start_positions = positions(&mut world);
+
+    for frame in 4..8 {
+        step(&mut world, frame);
+    }
+    let end = world.snapshot().unwrap();
+    let end_positions = positions(&mut world);
+    assert_ne!(start, end);
+
+    world.restore(&start);
+    assert_eq!(world.snapshot().unwrap(), start);
+    assert_eq!(positions(&mut world), start_positions);
+    for (entity, _) in &start_positions {
+        assert!(world.is_alive(entity));
+    }
+
+    for frame in 4..8 {
+        step(&mut world, frame);
+    }
+    assert_eq!(world.snapshot().unwrap(), end);
+    assert_eq!(positions(&mut world), end_positions);
+}
+
+#[test]
+fn restore_kills_entities_spawned_after_the_snapshot() {
+    let mut world = world();
+    let kept = world.spawn(Position { x: 1.0, y: 2.0 });
+    let snapshot = world.snapshot().unwrap();
+
+    let spawned = world.spawn((Position { x: 0.0, y: 0.0 }, Frozen));
+    world.despawn_entity(kept.clone());
+    world.restore(&snapshot);
+
+    assert!(world.is_alive(&kept));
+    assert!(!world.is_alive(&spawned));
+    assert_eq!(
+        world.get_component::<Position>(&kept),
+        Some(&Position { x: 1.0, y: 2.0 })
+    );
+    assert_eq!(world.spawn(Frozen), spawned);
+}
+
+#[test]
+fn restored_components_count_as_changed() {
+    let mut world = world();
+    let entity = world.spawn(Position { x: 0.0, y: 0.0 });
+    let snapshot = world.snapshot().unwrap();
+
+    let mut changed = 0;
+    world.system::<ecs_core::Changed<Position>, _>(|_, _| changed += 1);
+    assert_eq!(changed, 1);
+
+    world.restore(&snapshot);
+    let mut changed = vec![];
+    world.system::<ecs_core::Changed<Position>, _>(|entity, _| changed.push(entity));
+    assert_eq!(changed, vec![entity]);
+}
+
+#[test]
+fn unregistered_components_cannot_be_snapshot() {
+    #[derive(Component)]
+    struct Unregistered;
+
+    let mut world = world();
+    world.spawn(Unregistered);
+    assert!(matches!(
+        world.snapshot(),
+        Err(EcsError::NotSnapshottable(_))
+    ));
+}
@@ -0,0 +1,86 @@
+use ecs_core::{Changed, Component, Entity, StorageType, With, Without, World};
+
+#[derive(Debug, PartialEq)]
+struct A(u32);
+impl Component for A {}
+
+#[derive(Debug, PartialEq)]
+struct Marker(u32);
+impl Component for Marker {
+    const STORAGE_TYPE: StorageType = StorageType::SparseSet;
+}
+
+fn world() -> (World, Vec<Entity>) {
+    let mut world = World::new();
+    let entities = (0..6)
+        .map(|i| {
+            let entity = world.spawn_entity();
+            world.add_component(&entity, A(i));
+            entity
+        })
+        .collect();
+    (world, entities)
+}
+
+fn marked(world: &mut World) -> Vec<u32> {
+    let mut seen = vec![];
+    world.system::<(&A, With<Marker>), _>(|_, (a, _)| seen.push(a.0));
+    seen.sort();
+    seen
+}
+
+fn changed(world: &mut World) -> Vec<u32> {
+    let mut seen = vec![];
+    world.system::<(&Marker, Changed<Marker>), _>(|_, (marker, _)| seen.push(marker.0));
+    seen.sort();
+    seen
+}
+
+#[test]
+fn sparse_components_are_matched_and_fetched() {
+    let (mut world, entities) = world();
+    for entity in entities.iter().step_by(2) {
+        world.add_component(entity, Marker(10));
+    }
+
+    assert_eq!(marked(&mut world), [0, 2, 4]);
+    let mut unmarked = vec![];
+    world.system::<(&A, Without<Marker>), _>(|_, (a, _)| unmarked.push(a.0));
+    unmarked.sort();
+    assert_eq!(unmarked, [1, 3, 5]);
+
+    world.system::<&mut Marker, _>(|_, mut marker| marker.0 += 1);
+    assert_eq!(world.get_component(&entities[2]), Some(&Marker(11)));
+}
+
+#[test]
+fn toggling_sparse_components_keeps_the_rest_intact() {
+    let (mut world, entities) = world();
+    world.add_component(&entities[1], Marker(1));
+    world.add_component(&entities[3], Marker(3));
+
+    assert_eq!(
+        world.remove_component::<Marker>(&entities[1]),
+        Some(Marker(1))
+    );
+    world.despawn_entity(entities[0].clone());
+    world.add_component(&entities[5], Marker(5));
+
+    assert_eq!(marked(&mut world), [3, 5]);
+    assert_eq!(world.get_component(&entities[1]), Some(&A(1)));
+    assert_eq!(world.get_component::<Marker>(&entities[1]), None);
+    assert_eq!(world.get_component(&entities[3]), Some(&Marker(3)));
+}
+
+#[test]
+fn sparse_components_track_changes() {
+    let (mut world, entities) = world();
+    world.add_component(&entities[0], Marker(0));
+    world.add_component(&entities[1], Marker(1));
+
+    assert_eq!(changed(&mut world), [0, 1]);
+    assert_eq!(changed(&mut world), []);
+
+    world.get_component_mut::<Marker>(&entities[1]).unwrap().0 = 2;
+    assert_eq!(changed(&mut world), [2]);
+}
@@ -0,0 +1,181 @@
+use std::sync::{Arc, Mutex};
+
+use ecs_core::{
+    Children, Component, DynamicLayout, DynamicValue, EcsError, Entity, EntityMap, MapEntities,
+    Parent, StorageType, World,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Component, Debug, PartialEq)]
+struct Position(u32);
+
+#[derive(Component, Debug, PartialEq)]
+#[component(storage = "SparseSet")]
+struct Marker;
+
+#[derive(Component, Debug, PartialEq, Serialize, Deserialize)]
+#[component(reflect)]
+struct Target(Entity);
+
+impl MapEntities for Target {
+    fn map_entities(&mut self, entities: &EntityMap) -> Result<(), EcsError> {
+        entities.map_entity(&mut self.0)
+    }
+}
+
+#[test]
+fn components_and_hierarchies_move_with_their_entities() {
+    let mut from = World::new();
+    let mut to = World::new();
+    to.spawn(Position(0));
+    let root = from.spawn((Position(1), Marker));
+    let child = from.spawn(Position(2));
+    let left_behind = from.spawn(Position(3));
+    from.set_parent(&child, &root);
+    from.set_parent(&left_behind, &root);
+
+    let map = from
+        .transfer_entities(&[root.clone(), child.clone()], &mut to)
+        .unwrap();
+    assert!(!from.is_alive(&root) && !from.is_alive(&child));
+    assert!(from.get_component::<Parent>(&left_behind).is_none());
+
+    let [root, child] = [&root, &child].map(|entity| map.get(entity).unwrap().clone());
+    assert_eq!(to.get_component::<Position>(&root), Some(&Position(1)));
+    assert_eq!(to.get_component::<Marker>(&root), Some(&Marker));
+    assert_eq!(to.get_component::<Position>(&child), Some(&Position(2)));
+    assert_eq!(
+        to.get_component::<Parent>(&child).map(Parent::get),
+        Some(&root)
+    );
+    assert_eq!(
+        to.get_component::<Children>(&root).map(Children::len),
+        Some(1)
+    );
+}
+
+#[test]
+fn hooks_run_as_if_despawned_and_spawned() {
+    let log = Arc::new(Mutex::new(vec![]));
+    let mut from = World::new();
+    let mut to = World::new();
+    let removed = log.clone();
+    from.on_remove::<Position>(move |world, entity| {
+        let position = world.get_component::<Position>(entity).unwrap().0;
+        removed.lock().unwrap().push(("remove", position));
+    });
+    let added = log.clone();
+    to.on_add::<Position>(move |world, entity| {
+        let position = world.get_component::<Position>(entity).unwrap().0;
+        added.lock().unwrap().push(("add", position));
+    });
+
+    let entity = from.spawn(Position(1));
+    from.transfer_entity(&entity, &mut to).unwrap();
+    assert_eq!(*log.lock().unwrap(), [("remove", 1), ("add", 1)]);
+}
+
+#[test]
+fn entity_references_are_remapped() {
+    let mut from = World::new();
+    let mut to = World::new();
+    to.register_type_with_entities::<Target>();
+    to.spawn(Position(0));
+    let target = from.spawn(Position(1));
+    let follower = from.spawn(Target(target.clone()));
+
+    let map = from
+        .transfer_entities(&[target.clone(), follower.clone()], &mut to)
+        .unwrap();
+    assert_eq!(
+        to.get_component::<Target>(map.get(&follower).unwrap()),
+        Some(&Target(map.get(&target).unwrap().clone()))
+    );
+
+    // The target is left behind, so the reference can't be mapped.
+    let target = from.spawn(Position(2));
+    let follower = from.spawn(Target(target.clone()));
+    assert_eq!(
+        from.transfer_entity(&follower, &mut to),
+        Err(EcsError::UnknownSceneEntity(target))
+    );
+}
+
+#[test]
+fn dead_entities_fail_the_transfer() {
+    let mut from = World::new();
+    let mut to = World::new();
+    let alive = from.spawn(Position(1));
+    let dead = from.spawn(Position(2));
+    from.despawn_entity(dead.clone());
+
+    assert_eq!(
+        from.transfer_entities(&[alive.clone(), dead.clone()], &mut to)
+            .err(),
+        Some(EcsError::EntityNotFound(dead))
+    );
+    assert!(from.is_alive(&alive));
+}
+
+#[test]
+fn entities_despawned_by_a_remove_hook_are_not_moved() {
+    let mut from = World::new();
+    let mut to = World::new();
+    from.on_remove::<Position>(|world, entity| world.despawn_entity(entity.clone()));
+
+    let entity = from.spawn(Position(1));
+    assert_eq!(
+        from.transfer_entity(&entity, &mut to),
+        Err(EcsError::EntityNotFound(entity.clone()))
+    );
+    assert!(!from.is_alive(&entity));
+    assert!(
+        to.archetypes()
+            .iter()
+            .all(|archetype| archetype.entities().is_empty())
+    );
+}
+
+fn world_with(layout: DynamicLayout, storage_type: StorageType) -> (World, u32) {
+    let mut world = World::new();
+    let id = world.register_dynamic_component("health", layout, storage_type);
+    (world, id)
+}
+
+#[test]
+fn dynamic_components_move_into_matching_registrations() {
+    let (mut from, id) = world_with(DynamicLayout::Bytes(2), StorageType::Table);
+    let (mut to, to_id) = world_with(DynamicLayout::Bytes(2), StorageType::Table);
+    let entity = from.spawn_entity();
+    from.insert_dynamic(&entity, id, DynamicValue::Bytes(vec![1, 2]));
+
+    let moved = from.transfer_entity(&entity, &mut to).unwrap();
+    assert_eq!(
+        to.get_dynamic(&moved, to_id),
+        Some(&DynamicValue::Bytes(vec![1, 2]))
+    );
+}
+
+#[test]
+fn dynamic_layout_conflicts_fail_the_transfer() {
+    for (layout, storage_type) in [
+        (DynamicLayout::Bytes(4), StorageType::Table),
+        (DynamicLayout::Bytes(2), StorageType::SparseSet),
+    ] {
+        let (mut from, id) = world_with(DynamicLayout::Bytes(2), StorageType::Table);
+        let (mut to, _) = world_with(layout, storage_type);
+        let entity = from.spawn_entity();
+        from.insert_dynamic(&entity, id, DynamicValue::Bytes(vec![1, 2]));
+
+        assert_eq!(
+            from.transfer_entity(&entity, &mut to),
+            Err(EcsError::DynamicComponentConflict("health"))
+        );
+        assert!(from.is_alive(&entity));
+        assert!(
+            to.archetypes()
+                .iter()
+                .all(|archetype| archetype.entities().is_empty())
+        );
+    }
+}
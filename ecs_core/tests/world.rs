@@ -0,0 +1,88 @@
+use ecs_core::{Changed, Component, EcsError, World};
+
+#[derive(Debug, PartialEq)]
+struct A(u32);
+impl Component for A {}
+
+#[derive(Debug, PartialEq)]
+struct B(u32);
+impl Component for B {}
+
+#[test]
+fn insert_component_adds_or_replaces() {
+    let mut world = World::new();
+    let entity = world.spawn_entity();
+
+    assert_eq!(world.insert_component(&entity, A(1)), None);
+    let mut changed = 0;
+    world.system::<Changed<A>, _>(|_, _| changed += 1);
+    assert_eq!(changed, 1);
+
+    assert_eq!(world.insert_component(&entity, A(2)), Some(A(1)));
+    assert_eq!(world.get_component::<A>(&entity), Some(&A(2)));
+    world.system::<Changed<A>, _>(|_, _| changed += 1);
+    assert_eq!(changed, 2);
+}
+
+#[test]
+fn remove_component_keeps_other_rows_intact() {
+    let mut world = World::new();
+    let entities: Vec<_> = (0..3)
+        .map(|i| {
+            let entity = world.spawn_entity();
+            world.add_component(&entity, A(i));
+            world.add_component(&entity, B(i));
+            entity
+        })
+        .collect();
+
+    assert_eq!(world.remove_component::<A>(&entities[0]), Some(A(0)));
+    assert_eq!(world.remove_component::<A>(&entities[0]), None);
+    assert_eq!(world.get_component::<B>(&entities[0]), Some(&B(0)));
+
+    for (i, entity) in entities.iter().enumerate().skip(1) {
+        assert_eq!(world.get_component::<A>(entity), Some(&A(i as u32)));
+        assert_eq!(world.get_component::<B>(entity), Some(&B(i as u32)));
+    }
+
+    let mut count = 0;
+    world.system::<(&A, &B), _>(|_, _| count += 1);
+    assert_eq!(count, 2);
+}
+
+#[test]
+fn stale_handles_are_rejected_after_their_id_is_recycled() {
+    let mut world = World::new();
+    let stale = world.spawn_entity();
+    world.add_component(&stale, A(0));
+    world.despawn_entity(stale.clone());
+
+    let recycled = world.spawn_entity();
+    assert_eq!(recycled.id(), stale.id());
+    assert!(recycled.generation() > stale.generation());
+    assert!(!world.is_alive(&stale));
+    assert!(world.is_alive(&recycled));
+
+    assert_eq!(world.get_component::<A>(&stale), None);
+    assert_eq!(
+        world.try_despawn(stale.clone()),
+        Err(EcsError::EntityNotFound(stale.clone()))
+    );
+    assert_eq!(
+        world.try_add_component(&stale, A(1)),
+        Err(EcsError::EntityNotFound(stale))
+    );
+}
+
+#[test]
+fn adding_a_component_twice_fails() {
+    let mut world = World::new();
+    let entity = world.spawn_entity();
+
+    assert_eq!(world.try_add_component(&entity, A(1)), Ok(()));
+    assert!(matches!(
+        world.try_add_component(&entity, A(2)),
+        Err(EcsError::ComponentAlreadyExists { entity: e, .. }) if e == entity
+    ));
+    assert_eq!(world.get_component::<A>(&entity), Some(&A(1)));
+}
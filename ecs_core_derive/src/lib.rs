@@ -0,0 +1,205 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    Data, DeriveInput, Fields, Index, LitStr, ext::IdentExt, parse_macro_input, spanned::Spanned,
+};
+
+/// Implements `ecs_core::Component`.
+///
+/// # Attributes
+/// - `#[component(storage = "SparseSet")]` - Stores the component in a sparse set instead of the
+///   archetype table, see `ecs_core::StorageType`
+/// - `#[component(reflect)]` - Also implements `ecs_core::Reflect`, so the component can be
+///   registered with `World::register_type`. The struct and its fields must implement serde's
+///   `Serialize` and `Deserialize`
+#[proc_macro_derive(Component, attributes(component))]
+pub fn derive_component(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_component(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implements `ecs_core::Resource`.
+#[proc_macro_derive(Resource)]
+pub fn derive_resource(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_resource(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implements `ecs_core::Bundle` for a struct whose fields are all bundles or components.
+#[proc_macro_derive(Bundle)]
+pub fn derive_bundle(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_bundle(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand_component(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let mut storage = None;
+    let mut reflect = false;
+
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("component"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("storage") {
+                let value: LitStr = meta.value()?.parse()?;
+                storage = match value.value().as_str() {
+                    "Table" => Some(quote!(::ecs_core::StorageType::Table)),
+                    "SparseSet" => Some(quote!(::ecs_core::StorageType::SparseSet)),
+                    _ => {
+                        return Err(syn::Error::new(
+                            value.span(),
+                            "expected `storage = \"Table\"` or `storage = \"SparseSet\"`",
+                        ));
+                    }
+                };
+                Ok(())
+            } else if meta.path.is_ident("reflect") {
+                reflect = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported component attribute"))
+            }
+        })?;
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let storage = storage.map(|storage| {
+        quote! {
+            const STORAGE_TYPE: ::ecs_core::StorageType = #storage;
+        }
+    });
+    let reflect = if reflect {
+        expand_reflect(input)?
+    } else {
+        quote!()
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::ecs_core::Component for #name #ty_generics #where_clause {
+            #storage
+        }
+
+        #reflect
+    })
+}
+
+fn expand_resource(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::ecs_core::Resource for #name #ty_generics #where_clause {}
+    })
+}
+
+fn expand_bundle(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new(
+            input.span(),
+            "Bundle can only be derived for structs",
+        ));
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let types: Vec<_> = data.fields.iter().map(|field| &field.ty).collect();
+    let members: Vec<_> = members(&data.fields);
+
+    Ok(quote! {
+        impl #impl_generics ::ecs_core::Bundle for #name #ty_generics #where_clause {
+            fn component_ids(
+                world: &mut ::ecs_core::World,
+                ids: &mut ::std::vec::Vec<::ecs_core::ComponentId>,
+            ) {
+                #(<#types as ::ecs_core::Bundle>::component_ids(world, ids);)*
+            }
+
+            fn push_components(self, world: &mut ::ecs_core::World, entity: &::ecs_core::Entity) {
+                #(::ecs_core::Bundle::push_components(self.#members, world, entity);)*
+            }
+        }
+    })
+}
+
+fn expand_reflect(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new(
+            input.span(),
+            "reflect is only supported for structs",
+        ));
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let members = members(&data.fields);
+    let type_name = name.unraw().to_string();
+    let field_names: Vec<String> = members
+        .iter()
+        .map(|member| match member {
+            syn::Member::Named(ident) => ident.unraw().to_string(),
+            syn::Member::Unnamed(index) => index.index.to_string(),
+        })
+        .collect();
+
+    Ok(quote! {
+        impl #impl_generics ::ecs_core::Reflect for #name #ty_generics #where_clause {
+            fn field_names() -> &'static [&'static str] {
+                &[#(#field_names),*]
+            }
+
+            fn field(
+                &self,
+                name: &str,
+            ) -> ::std::result::Result<::ecs_core::ReflectValue, ::ecs_core::EcsError> {
+                match name {
+                    #(#field_names => ::ecs_core::to_reflect_value(&self.#members),)*
+                    _ => ::std::result::Result::Err(::ecs_core::EcsError::UnknownField {
+                        component: #type_name.to_string(),
+                        field: name.to_string(),
+                    }),
+                }
+            }
+
+            // `value` is unused for structs without fields.
+            #[allow(unused_variables)]
+            fn set_field(
+                &mut self,
+                name: &str,
+                value: ::ecs_core::ReflectValue,
+            ) -> ::std::result::Result<(), ::ecs_core::EcsError> {
+                match name {
+                    #(#field_names => {
+                        self.#members = ::ecs_core::from_reflect_value(value)?;
+                        ::std::result::Result::Ok(())
+                    })*
+                    _ => ::std::result::Result::Err(::ecs_core::EcsError::UnknownField {
+                        component: #type_name.to_string(),
+                        field: name.to_string(),
+                    }),
+                }
+            }
+        }
+    })
+}
+
+/// The members used to access each field, i.e. `name` for named fields and `0` for tuple fields.
+fn members(fields: &Fields) -> Vec<syn::Member> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| match &field.ident {
+            Some(ident) => syn::Member::Named(ident.clone()),
+            None => syn::Member::Unnamed(Index::from(index)),
+        })
+        .collect()
+}
@@ -1,4 +1,5 @@
 use anyhow::anyhow;
+use ecs_core::{EntityMap, Scene, World};
 use serde::Deserialize;
 use std::{
     fs,
@@ -99,3 +100,9 @@ pub fn load_block(dir: &Path, def: TileDef) -> anyhow::Result<TileAsset> {
         animation: def.animation,
     })
 }
+
+pub fn load_scene(path: &Path, world: &mut World) -> anyhow::Result<EntityMap> {
+    let scene_str = fs::read_to_string(path)?;
+    let scene = Scene::from_ron(&scene_str)?;
+    Ok(world.load_scene(&scene)?)
+}
@@ -1,10 +1,11 @@
 use std::{
     collections::HashSet,
+    path::Path,
     sync::Arc,
     time::{Duration, Instant},
 };
 
-use ecs_core::spawn_entity;
+use ecs_core::{Changed, ExclusiveSystem, QuerySystem, Res, ResMut, Schedule, Stage, World};
 use winit::{
     application::ApplicationHandler,
     event::StartCause,
@@ -14,13 +15,18 @@ use winit::{
 };
 
 use crate::{
-    game_logic::{GameWorld, Position, Sprite, Velocity},
+    assets::load_scene,
+    game_logic::{
+        DeltaTime, ExtractedEntities, GameWorld, GlobalPosition, Position, RebuildMesh, Sprite,
+        Velocity, propagate_positions,
+    },
     graphics::Graphics,
     mesh::WorldMesh,
 };
 
 struct GameManager {
     last_frame: Instant,
+    last_update: Instant,
     target_frame_duration: Duration,
 
     start: Instant,
@@ -32,7 +38,7 @@ struct GameManager {
     world_mesh: Option<WorldMesh>,
 
     game_world: GameWorld,
-    player: ecs_core::Entity,
+    schedule: Schedule,
 
     _pressed_named_keys: HashSet<NamedKey>,
     _pressed_keys: HashSet<SmolStr>,
@@ -45,27 +51,102 @@ impl GameManager {
             Ok(game_world) => game_world,
             Err(e) => panic!("{e}"),
         };
-        let player = spawn_entity!(
-            game_world.world,
-            (
-                Position {
-                    x: 6.0,
-                    y: 6.0,
-                    z: 0.0,
-                },
-                Velocity {
-                    x: -0.01,
-                    y: -0.01,
-                    z: 0.0,
+        game_world.world.insert_resource(DeltaTime { seconds: 0.0 });
+        game_world
+            .world
+            .insert_resource(ExtractedEntities::default());
+        // Entities that lose their sprite, including by being despawned, must stop rendering.
+        game_world.world.on_remove::<Sprite>(|world, entity| {
+            if let Some(extracted) = world.resource_mut::<ExtractedEntities>() {
+                extracted.entities.remove(entity);
+                extracted.removed.push(entity.clone());
+            }
+        });
+
+        if let Err(e) = load_scene(
+            Path::new("src/assets/scenes/start.ron"),
+            &mut game_world.world,
+        ) {
+            panic!("{e}");
+        }
+
+        let mut schedule = Schedule::new();
+        schedule.add_system(
+            "movement",
+            Stage::Update,
+            QuerySystem::<(&mut Position, &Velocity, Res<DeltaTime>), _>::new(
+                |_, (mut pos, vel, delta_time)| {
+                    pos.x += vel.x * delta_time.seconds;
+                    pos.y += vel.y * delta_time.seconds;
+                    pos.z += vel.z * delta_time.seconds;
                 },
-                Sprite {
-                    texture_name: String::from("grass")
+            ),
+        );
+
+        schedule.add_system(
+            "propagate_positions",
+            Stage::PostUpdate,
+            ExclusiveSystem::new(propagate_positions),
+        );
+        schedule.add_system(
+            "extract_all",
+            Stage::RenderExtract,
+            ExclusiveSystem::new(|world: &mut World| {
+                if world.remove_resource::<RebuildMesh>().is_none() {
+                    return;
                 }
-            )
+                world.system::<(&GlobalPosition, &Sprite, ResMut<ExtractedEntities>), _>(
+                    |entity, (pos, sprite, extracted)| {
+                        extracted.entities.insert(
+                            entity.clone(),
+                            crate::game_logic::Entity::new(entity, pos, sprite),
+                        );
+                    },
+                );
+            }),
+        );
+
+        // Only entities whose position or sprite changed need their mesh rebuilt.
+        schedule.add_system(
+            "extract_moved",
+            Stage::RenderExtract,
+            QuerySystem::<
+                (
+                    &GlobalPosition,
+                    &Sprite,
+                    Changed<GlobalPosition>,
+                    ResMut<ExtractedEntities>,
+                ),
+                _,
+            >::new(|entity, (pos, sprite, _, extracted)| {
+                extracted.entities.insert(
+                    entity.clone(),
+                    crate::game_logic::Entity::new(entity, pos, sprite),
+                );
+            }),
+        );
+        schedule.add_system(
+            "extract_sprites",
+            Stage::RenderExtract,
+            QuerySystem::<
+                (
+                    &GlobalPosition,
+                    &Sprite,
+                    Changed<Sprite>,
+                    ResMut<ExtractedEntities>,
+                ),
+                _,
+            >::new(|entity, (pos, sprite, _, extracted)| {
+                extracted.entities.insert(
+                    entity.clone(),
+                    crate::game_logic::Entity::new(entity, pos, sprite),
+                );
+            }),
         );
 
         Self {
             last_frame: Instant::now(),
+            last_update: Instant::now(),
             target_frame_duration: Duration::from_secs_f64(1.0 / 120.0),
             start: Instant::now(),
             window: None,
@@ -74,7 +155,7 @@ impl GameManager {
             world_mesh: None,
 
             game_world,
-            player,
+            schedule,
 
             _pressed_named_keys: HashSet::new(),
             _pressed_keys: HashSet::new(),
@@ -92,20 +173,26 @@ impl ApplicationHandler for GameManager {
         let now = Instant::now();
         let next_frame_time = self.last_frame + self.target_frame_duration;
 
-        self.game_world
-            .world
-            .system::<(Position, Velocity, Option<Sprite>), _>(|entity, (pos, vel, sprite)| {
-                pos.x += vel.x;
-                pos.y += vel.y;
-                pos.z += vel.z;
-                if let (Some(world_mesh), Some(sprite)) = (&mut self.world_mesh, sprite) {
-                    world_mesh.update_entity(crate::game_logic::Entity {
-                        entity: entity.clone(),
-                        pos: [pos.x, pos.y, pos.z],
-                        texture_name: sprite.texture_name.clone(),
-                    });
-                }
-            });
+        let world = &mut self.game_world.world;
+
+        if let Some(delta_time) = world.resource_mut::<DeltaTime>() {
+            delta_time.seconds = (now - self.last_update).as_secs_f32();
+        }
+        self.last_update = now;
+
+        self.schedule.run(world);
+
+        // Extracted entities are kept until there is a mesh to hand them to.
+        if let Some(world_mesh) = &mut self.world_mesh
+            && let Some(extracted) = world.resource_mut::<ExtractedEntities>()
+        {
+            for entity in extracted.removed.drain(..) {
+                world_mesh.remove_entity(&entity);
+            }
+            for (_, entity) in extracted.entities.drain() {
+                world_mesh.update_entity(entity);
+            }
+        }
 
         if now >= next_frame_time || matches!(cause, StartCause::Init) {
             if let Some(window) = &self.window {
@@ -143,18 +230,7 @@ impl ApplicationHandler for GameManager {
             match WorldMesh::new(graphics, 0.1) {
                 Ok(mut world_mesh) => {
                     world_mesh.update_chunk(self.game_world.chunk.clone());
-                    self.game_world
-                        .world
-                        .entity_system::<(Position, Sprite), _>(
-                            &self.player,
-                            |entity, (pos, sprite)| {
-                                world_mesh.update_entity(crate::game_logic::Entity {
-                                    entity: entity.clone(),
-                                    pos: [pos.x, pos.y, pos.z],
-                                    texture_name: sprite.texture_name.clone(),
-                                })
-                            },
-                        );
+                    self.game_world.world.insert_resource(RebuildMesh);
                     self.world_mesh = Some(world_mesh)
                 }
                 Err(e) => eprintln!("Error creating chunk meshes: {e}"),
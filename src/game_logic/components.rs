@@ -1,22 +1,35 @@
 use ecs_core::Component;
+use serde::{Deserialize, Serialize};
 
 use crate::graphics::TextureHandle;
 
+/// The position of an entity, relative to its `Parent` if it has one.
+#[derive(Component, Clone, PartialEq, Serialize, Deserialize)]
+#[component(reflect)]
 pub struct Position {
     pub x: f32,
     pub y: f32,
     pub z: f32,
 }
-impl Component for Position {}
 
+#[derive(Component, Clone, PartialEq, Serialize, Deserialize)]
+#[component(reflect)]
 pub struct Velocity {
     pub x: f32,
     pub y: f32,
     pub z: f32,
 }
-impl Component for Velocity {}
 
+#[derive(Component, Clone, PartialEq, Serialize, Deserialize)]
+#[component(reflect)]
 pub struct Sprite {
     pub texture_name: String,
 }
-impl Component for Sprite {}
+
+/// The absolute position of an entity, propagated from its `Position` and those of its ancestors.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq)]
+pub struct GlobalPosition {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
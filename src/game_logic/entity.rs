@@ -1,5 +1,17 @@
+use crate::game_logic::{GlobalPosition, Sprite};
+
 pub struct Entity {
     pub entity: ecs_core::Entity,
     pub pos: [f32; 3],
     pub texture_name: String,
 }
+
+impl Entity {
+    pub fn new(entity: ecs_core::Entity, pos: &GlobalPosition, sprite: &Sprite) -> Self {
+        Self {
+            entity,
+            pos: [pos.x, pos.y, pos.z],
+            texture_name: sprite.texture_name.clone(),
+        }
+    }
+}
@@ -1,8 +1,14 @@
 mod components;
 pub use components::*;
 
+mod resources;
+pub use resources::*;
+
 mod world;
 pub use world::*;
 
 mod entity;
 pub use entity::*;
+
+mod systems;
+pub use systems::*;
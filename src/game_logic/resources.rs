@@ -0,0 +1,23 @@
+use std::collections::HashMap;
+
+use ecs_core::Resource;
+
+use crate::game_logic::Entity;
+
+#[derive(Resource)]
+pub struct DeltaTime {
+    pub seconds: f32,
+}
+
+/// Entities whose mesh needs rebuilding, collected by the render extract systems, and entities
+/// whose mesh needs dropping because their `Sprite` was removed.
+#[derive(Default, Resource)]
+pub struct ExtractedEntities {
+    pub entities: HashMap<ecs_core::Entity, Entity>,
+    pub removed: Vec<ecs_core::Entity>,
+}
+
+/// Inserted when a new mesh is created, so every entity is extracted on the next run instead
+/// of only the changed ones.
+#[derive(Resource)]
+pub struct RebuildMesh;
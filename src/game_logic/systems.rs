@@ -0,0 +1,95 @@
+use ecs_core::{Children, Parent, Without, World};
+
+use crate::game_logic::{GlobalPosition, Position};
+
+/// Updates the `GlobalPosition` of every entity with a `Position`, walking down the hierarchy
+/// from each root. Entities only get a new `GlobalPosition` when it actually moved, so
+/// `Changed<GlobalPosition>` stays meaningful.
+///
+/// An entity without a `Position` sits at the origin of its parent, so its children are still
+/// positioned relative to that parent, but it gets no `GlobalPosition` itself.
+pub fn propagate_positions(world: &mut World) {
+    let mut stack = vec![];
+    world.system::<(Option<&Position>, Option<&Children>, Without<Parent>), _>(
+        |entity, (pos, children, _)| {
+            if pos.is_some() || children.is_some() {
+                stack.push((entity, GlobalPosition::default()));
+            }
+        },
+    );
+
+    while let Some((entity, parent)) = stack.pop() {
+        let global = match world.get_component::<Position>(&entity) {
+            Some(pos) => {
+                let global = GlobalPosition {
+                    x: parent.x + pos.x,
+                    y: parent.y + pos.y,
+                    z: parent.z + pos.z,
+                };
+                if world.get_component::<GlobalPosition>(&entity) != Some(&global) {
+                    world.insert_component(&entity, global);
+                }
+                global
+            }
+            None => parent,
+        };
+        if let Some(children) = world.get_component::<Children>(&entity) {
+            stack.extend(children.iter().map(|child| (child.clone(), global)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(x: f32) -> Position {
+        Position { x, y: 0.0, z: 0.0 }
+    }
+
+    fn global_x(world: &World, entity: &ecs_core::Entity) -> Option<f32> {
+        world
+            .get_component::<GlobalPosition>(entity)
+            .map(|global| global.x)
+    }
+
+    #[test]
+    fn positions_are_relative_to_the_parent() {
+        let mut world = World::new();
+        let root = world.spawn(position(1.0));
+        let child = world.spawn(position(2.0));
+        let grandchild = world.spawn(position(4.0));
+        world.set_parent(&child, &root);
+        world.set_parent(&grandchild, &child);
+
+        propagate_positions(&mut world);
+        assert_eq!(global_x(&world, &root), Some(1.0));
+        assert_eq!(global_x(&world, &child), Some(3.0));
+        assert_eq!(global_x(&world, &grandchild), Some(7.0));
+
+        world.get_component_mut::<Position>(&root).unwrap().x = 10.0;
+        propagate_positions(&mut world);
+        assert_eq!(global_x(&world, &grandchild), Some(16.0));
+    }
+
+    #[test]
+    fn entities_without_a_position_pass_on_their_parents_origin() {
+        let mut world = World::new();
+        let root = world.spawn(position(1.0));
+        let group = world.spawn_entity();
+        let child = world.spawn(position(2.0));
+        world.set_parent(&group, &root);
+        world.set_parent(&child, &group);
+
+        // A root without a position is at the world origin.
+        let unplaced_root = world.spawn_entity();
+        let other = world.spawn(position(5.0));
+        world.set_parent(&other, &unplaced_root);
+
+        propagate_positions(&mut world);
+        assert_eq!(global_x(&world, &group), None);
+        assert_eq!(global_x(&world, &child), Some(3.0));
+        assert_eq!(global_x(&world, &unplaced_root), None);
+        assert_eq!(global_x(&world, &other), Some(5.0));
+    }
+}
@@ -1,7 +1,10 @@
 use anyhow::Ok;
 use ecs_core::World;
 
-use crate::map::Chunk;
+use crate::{
+    game_logic::{GlobalPosition, Position, Sprite, Velocity},
+    map::Chunk,
+};
 
 pub struct GameWorld {
     pub world: World,
@@ -10,9 +13,42 @@ pub struct GameWorld {
 
 impl GameWorld {
     pub fn new() -> anyhow::Result<Self> {
+        let mut world = World::new();
+        world.register_type::<Position>();
+        world.register_type::<Velocity>();
+        world.register_type::<Sprite>();
+        world.register_snapshot::<Position>();
+        world.register_snapshot::<Velocity>();
+        world.register_snapshot::<Sprite>();
+        world.register_snapshot::<GlobalPosition>();
+
         Ok(Self {
-            world: World::new(),
+            world,
             chunk: Chunk::new([0, 0], 2)?,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::assets::load_scene;
+
+    #[test]
+    fn starting_scene_only_uses_registered_types() {
+        let mut game_world = GameWorld::new().unwrap();
+        let entities = load_scene(
+            Path::new("src/assets/scenes/start.ron"),
+            &mut game_world.world,
+        )
+        .unwrap();
+
+        assert!(!entities.is_empty());
+        for (_, entity) in entities.iter() {
+            assert!(game_world.world.get_component::<Position>(entity).is_some());
+            assert!(game_world.world.get_component::<Sprite>(entity).is_some());
+        }
+    }
+}
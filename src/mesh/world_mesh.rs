@@ -85,6 +85,11 @@ impl WorldMesh {
             .insert(entity.entity.clone(), entity);
     }
 
+    pub fn remove_entity(&mut self, entity: &ecs_core::Entity) {
+        self.entities_to_update.remove(entity);
+        self.entities_to_render.remove(entity);
+    }
+
     pub fn update_chunk(&mut self, chunk: Chunk) {
         let pos = chunk.pos;
         let pos = (pos[0], pos[1]);